@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use reqwest::Client;
-use crate::types::{Coin, GlobalMarketStats, PriceHistory, SearchResult};
+use crate::types::{Candle, Coin, CoinDetails, GlobalMarketStats, MarketCategory, PricePoint, SearchResult};
 
 const BASE_URL: &str = "https://api.coingecko.com/api/v3";
 const PRO_BASE_URL: &str = "https://pro-api.coingecko.com/api/v3";
+/// Largest `per_page` the markets endpoint accepts.
+const MAX_PER_PAGE: u32 = 250;
 
 pub struct CoinGeckoClient {
     client: Client,
@@ -40,13 +42,10 @@ impl CoinGeckoClient {
         }
     }
 
-    pub async fn fetch_markets(&self, limit: u32) -> Result<Vec<Coin>> {
-        let url = format!(
-            "{}/coins/markets?vs_currency={}&order=market_cap_desc&per_page={}&page=1&sparkline=false&price_change_percentage=1h,24h,7d",
-            self.base_url(), self.currency, limit
-        );
+    /// Send `req` with the API key attached, failing on an error status.
+    async fn send(&self, req: reqwest::RequestBuilder) -> Result<reqwest::Response> {
         let resp = self
-            .apply_auth(self.client.get(&url))
+            .apply_auth(req)
             .header("Accept", "application/json")
             .send()
             .await
@@ -57,6 +56,37 @@ impl CoinGeckoClient {
             let body = resp.text().await.unwrap_or_default();
             anyhow::bail!("CoinGecko API error {}: {}", status, body);
         }
+        Ok(resp)
+    }
+
+    /// The top `limit` coins by market cap, optionally within one category,
+    /// fetched in pages of up to 250.
+    pub async fn fetch_markets(&self, limit: u32, category: Option<&str>) -> Result<Vec<Coin>> {
+        let per_page = limit.clamp(1, MAX_PER_PAGE);
+        let mut coins = Vec::with_capacity(limit as usize);
+        for page in 1..=limit.div_ceil(per_page) {
+            let batch = self.fetch_markets_page(per_page, page, category).await?;
+            let last = (batch.len() as u32) < per_page;
+            coins.extend(batch);
+            if last {
+                break;
+            }
+        }
+        coins.truncate(limit as usize);
+        Ok(coins)
+    }
+
+    /// One page of coins by market cap; page numbers start at 1.
+    pub async fn fetch_markets_page(&self, per_page: u32, page: u32, category: Option<&str>) -> Result<Vec<Coin>> {
+        let url = format!(
+            "{}/coins/markets?vs_currency={}&order=market_cap_desc&per_page={}&page={}&sparkline=false&price_change_percentage=1h,24h,7d",
+            self.base_url(), self.currency, per_page, page
+        );
+        let mut req = self.client.get(&url);
+        if let Some(category) = category {
+            req = req.query(&[("category", category)]);
+        }
+        let resp = self.send(req).await?;
 
         let text = resp.text().await.context("Failed to read response body")?;
         let coins: Vec<Coin> = match serde_json::from_str(&text) {
@@ -72,39 +102,82 @@ impl CoinGeckoClient {
         Ok(coins)
     }
 
-    pub async fn fetch_price_history(&self, coin_id: &str, days: u32) -> Result<PriceHistory> {
+    /// Price and volume samples for the last `days` days (a number or "max").
+    /// Beyond 90 days CoinGecko returns one sample per day.
+    pub async fn fetch_price_history(&self, coin_id: &str, days: &str) -> Result<Vec<PricePoint>> {
         let url = format!(
             "{}/coins/{}/market_chart?vs_currency={}&days={}",
             self.base_url(), coin_id, self.currency, days
         );
-        let resp = self
-            .apply_auth(self.client.get(&url))
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .context("Failed to reach CoinGecko API")?;
+        self.fetch_price_points(&url).await
+    }
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("CoinGecko API error {}: {}", status, body);
-        }
+    /// Price and volume samples between two unix timestamps. CoinGecko
+    /// picks the granularity from the span: 5-minutely within a day, hourly
+    /// up to 90 days.
+    pub async fn fetch_price_range(&self, coin_id: &str, from: i64, to: i64) -> Result<Vec<PricePoint>> {
+        let url = format!(
+            "{}/coins/{}/market_chart/range?vs_currency={}&from={}&to={}",
+            self.base_url(), coin_id, self.currency, from, to
+        );
+        self.fetch_price_points(&url).await
+    }
+
+    async fn fetch_price_points(&self, url: &str) -> Result<Vec<PricePoint>> {
+        let resp = self.send(self.client.get(url)).await?;
 
         let data: serde_json::Value = resp.json().await?;
         let price_points = data["prices"]
             .as_array()
             .context("Missing prices array")?;
 
-        let mut prices = Vec::with_capacity(price_points.len());
+        // Volumes share the price timestamps; match by index
+        let empty = Vec::new();
+        let volumes = data["total_volumes"].as_array().unwrap_or(&empty);
 
-        for point in price_points {
+        let mut points = Vec::with_capacity(price_points.len());
+
+        for (i, point) in price_points.iter().enumerate() {
             let arr = point.as_array().context("Invalid price point")?;
             if arr.len() >= 2 {
-                prices.push(arr[1].as_f64().unwrap_or(0.0));
+                let ts_ms = arr[0].as_f64().unwrap_or(0.0);
+                let volume = volumes
+                    .get(i)
+                    .and_then(|v| v.get(1))
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0);
+                points.push(PricePoint {
+                    ts: (ts_ms / 1000.0) as i64,
+                    price: arr[1].as_f64().unwrap_or(0.0),
+                    volume,
+                });
             }
         }
 
-        Ok(PriceHistory { prices })
+        Ok(points)
+    }
+
+    /// OHLC candles for the last `days` days (1, 7, 14, 30, 90, 180, 365 or
+    /// "max"). CoinGecko sets the candle size: 30 minutes up to 2 days,
+    /// 4 hours up to 30 days, 4 days beyond.
+    pub async fn fetch_ohlc(&self, coin_id: &str, days: &str) -> Result<Vec<Candle>> {
+        let url = format!(
+            "{}/coins/{}/ohlc?vs_currency={}&days={}",
+            self.base_url(), coin_id, self.currency, days
+        );
+        let resp = self.send(self.client.get(&url)).await?;
+
+        let rows: Vec<[f64; 5]> = resp.json().await.context("Failed to parse OHLC data")?;
+        Ok(rows
+            .into_iter()
+            .map(|[ts_ms, open, high, low, close]| Candle {
+                ts: (ts_ms / 1000.0) as i64,
+                open,
+                high,
+                low,
+                close,
+            })
+            .collect())
     }
 
     pub async fn search_coins(&self, query: &str) -> Result<Vec<SearchResult>> {
@@ -113,18 +186,7 @@ impl CoinGeckoClient {
             self.base_url(),
             query
         );
-        let resp = self
-            .apply_auth(self.client.get(&url))
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .context("Failed to reach CoinGecko API")?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("CoinGecko API error {}: {}", status, body);
-        }
+        let resp = self.send(self.client.get(&url)).await?;
 
         let data: serde_json::Value = resp.json().await?;
         let coins = data["coins"]
@@ -148,42 +210,83 @@ impl CoinGeckoClient {
     }
 
     pub async fn fetch_coin_market(&self, coin_id: &str) -> Result<Option<Coin>> {
+        Ok(self.fetch_coins_market(&[coin_id.to_string()]).await?.into_iter().next())
+    }
+
+    /// Market rows for specific coins, requested up to 250 ids at a time.
+    /// Each batch comes back in market cap order.
+    pub async fn fetch_coins_market(&self, coin_ids: &[String]) -> Result<Vec<Coin>> {
+        let mut coins = Vec::with_capacity(coin_ids.len());
+        for batch in coin_ids.chunks(MAX_PER_PAGE as usize) {
+            let url = format!(
+                "{}/coins/markets?vs_currency={}&ids={}&per_page={}&sparkline=false&price_change_percentage=1h,24h,7d",
+                self.base_url(), self.currency, batch.join(","), MAX_PER_PAGE
+            );
+            let resp = self.send(self.client.get(&url)).await?;
+            let batch: Vec<Coin> = resp.json().await.context("Failed to parse coin data")?;
+            coins.extend(batch);
+        }
+        Ok(coins)
+    }
+
+    /// Ids of the coins trending in CoinGecko searches over the last 24h,
+    /// most searched first.
+    pub async fn fetch_trending(&self) -> Result<Vec<String>> {
+        let url = format!("{}/search/trending", self.base_url());
+        let resp = self.send(self.client.get(&url)).await?;
+
+        let data: serde_json::Value = resp.json().await?;
+        let coins = data["coins"]
+            .as_array()
+            .context("Missing coins array")?;
+
+        Ok(coins
+            .iter()
+            .filter_map(|c| c["item"]["id"].as_str().map(str::to_string))
+            .collect())
+    }
+
+    /// Categories, description and links for one coin.
+    pub async fn fetch_coin_details(&self, coin_id: &str) -> Result<CoinDetails> {
         let url = format!(
-            "{}/coins/markets?vs_currency={}&ids={}&sparkline=false&price_change_percentage=1h,24h,7d",
-            self.base_url(), self.currency, coin_id
+            "{}/coins/{}?localization=false&tickers=false&market_data=false&community_data=false&developer_data=false&sparkline=false",
+            self.base_url(), coin_id
         );
-        let resp = self
-            .apply_auth(self.client.get(&url))
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .context("Failed to reach CoinGecko API")?;
+        let resp = self.send(self.client.get(&url)).await?;
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("CoinGecko API error {}: {}", status, body);
-        }
+        let data: serde_json::Value = resp.json().await?;
+        let strings = |v: &serde_json::Value| -> Vec<String> {
+            v.as_array()
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|s| s.as_str())
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        Ok(CoinDetails {
+            categories: strings(&data["categories"]),
+            description: strip_html(data["description"]["en"].as_str().unwrap_or_default()),
+            homepage: strings(&data["links"]["homepage"]).into_iter().next(),
+            explorers: strings(&data["links"]["blockchain_site"]).into_iter().take(3).collect(),
+        })
+    }
+
+    /// Every category with its aggregated market cap, 24h change and volume.
+    pub async fn fetch_categories(&self) -> Result<Vec<MarketCategory>> {
+        let url = format!("{}/coins/categories?order=market_cap_desc", self.base_url());
+        let resp = self.send(self.client.get(&url)).await?;
 
-        let coins: Vec<Coin> = resp.json().await.context("Failed to parse coin data")?;
-        Ok(coins.into_iter().next())
+        resp.json().await.context("Failed to parse categories")
     }
 
     pub async fn fetch_global(&self) -> Result<GlobalMarketStats> {
         let url = format!("{}/global", self.base_url());
 
-        let resp = self
-            .apply_auth(self.client.get(&url))
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .context("Failed to reach CoinGecko API")?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            anyhow::bail!("CoinGecko API error {}: {}", status, body);
-        }
+        let resp = self.send(self.client.get(&url)).await?;
 
         let data: serde_json::Value = resp.json().await?;
         let d = &data["data"];
@@ -226,3 +329,20 @@ impl CoinGeckoClient {
         Ok((value, label))
     }
 }
+
+/// Drop tags such as `<a href="...">` and collapse the CRLF paragraph breaks
+/// CoinGecko uses in descriptions.
+fn strip_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            '\r' => {}
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.replace("&amp;", "&").replace("&quot;", "\"").replace("&#39;", "'").trim().to_string()
+}
@@ -1,19 +1,46 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::sync::Arc;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use ratatui::layout::Rect;
 use tokio::sync::Mutex;
 
 use crate::api::CoinGeckoClient;
 use crate::config::Config;
 use crate::db::Db;
-use crate::notifications;
+use crate::digest::{self, DigestState};
+use crate::notifications::{self, AlertMessage, DeliveryEvent, NotificationSettings, NotificationSink};
 use crate::theme::{self, Theme, THEME_NAMES};
 use crate::types::*;
 
+/// How far back volume/rank samples are kept for alert evaluation.
+const MARKET_HISTORY_SECS: i64 = 24 * 60 * 60;
+/// Minimum prior samples before a volume spike alert can fire.
+const MIN_VOLUME_SAMPLES: usize = 3;
+/// How often the notification outbox is checked for retries.
+const OUTBOX_FLUSH_SECS: u64 = 15;
+/// How often the digest schedule is checked.
+const DIGEST_CHECK_SECS: u64 = 60;
+/// Coins fetched per page when loading the Markets tab on scroll.
+pub const LAZY_PAGE_SIZE: u32 = 50;
+/// Coin profiles (description, links) are refetched after this long.
+const DETAILS_MAX_AGE_SECS: i64 = 24 * 60 * 60;
+/// Sector totals are refetched after this long; CoinGecko updates them every 5 minutes.
+const CATEGORIES_MAX_AGE_SECS: i64 = 5 * 60;
+/// The trending list is refetched after this long.
+const TRENDING_MAX_AGE_SECS: i64 = 10 * 60;
+/// Coins per gainers/losers list on the Discover tab.
+pub const DISCOVER_MOVERS: usize = 10;
+/// Positions below this share of the portfolio are grouped as "Other".
+pub const ALLOCATION_OTHER_PCT: f64 = 2.0;
+
 pub struct App {
     pub tab: Tab,
+    /// The Markets list followed by tracked coins outside it
     pub coins: Vec<Coin>,
+    /// How many of `coins` belong to the Markets list
+    pub markets_len: usize,
     pub selected: usize,
     pub scroll_offset: usize,
     pub page_height: usize,
@@ -21,6 +48,26 @@ pub struct App {
     pub chart_view: ChartView,
     pub chart_cache: HashMap<(String, u32), PriceHistory>,
     pub loading_chart: bool,
+    /// Chart ranges already brought up to date from the network this session
+    pub chart_synced: HashSet<(String, u32)>,
+    pub chart_mode: ChartMode,
+    /// Log price axis; `None` follows the range (on for 1Y and Max)
+    pub chart_log: Option<bool>,
+    pub chart_ma: MovingAverage,
+    pub chart_indicators: ChartIndicators,
+    /// Profiles from `/coins/{id}`, loaded when a coin's detail page opens
+    pub coin_details: HashMap<String, CoinDetails>,
+    details_synced: HashSet<String>,
+    /// Coins marked for the compare chart, in the order they were marked
+    pub compare_ids: Vec<String>,
+    pub compare_open: bool,
+    /// OHLC candles per (coin, days), fetched on demand for candle mode
+    pub ohlc_cache: HashMap<(String, u32), Vec<Candle>>,
+    pub ohlc_synced: HashSet<(String, u32)>,
+    /// Inspected point in the open chart; `None` follows the latest price
+    pub chart_cursor: Option<usize>,
+    /// Plot area of the open chart, for mouse inspection
+    pub chart_area: Option<Rect>,
     pub input_mode: InputMode,
     pub input_buf: String,
     pub db: Option<Arc<Mutex<Db>>>,
@@ -28,6 +75,10 @@ pub struct App {
     pub holdings: Vec<Holding>,
     pub last_refresh: Option<std::time::Instant>,
     pub last_refresh_display: String,
+    /// When the market data on screen was fetched
+    pub markets_fetched_at: Option<i64>,
+    /// Set while showing a snapshot or data from before a failed refresh
+    pub stale_since: Option<i64>,
     pub error: Option<String>,
     pub loading: bool,
     pub config: Config,
@@ -45,6 +96,33 @@ pub struct App {
     pub settings_cmc_key: String,
     pub settings_currency_idx: usize,
     pub settings_theme_idx: usize,
+    pub settings_markets_idx: usize,
+    pub settings_lazy_load: bool,
+    /// How many top coins the Markets tab has fetched so far; below
+    /// `config.markets_limit` only while lazy loading
+    pub markets_loaded: u32,
+    /// Sector the Markets tab is narrowed to, with its coins kept apart from
+    /// `coins` so favourites, holdings and alerts are unaffected
+    pub market_category: Option<MarketCategory>,
+    pub sector_coins: Vec<Coin>,
+    // Sector overview
+    pub categories: Vec<MarketCategory>,
+    categories_fetched_at: Option<i64>,
+    pub sector_query: String,
+    pub sector_selected: usize,
+    pub sector_sort: SectorSort,
+    // Discover
+    pub trending_coins: Vec<Coin>,
+    /// Currency and time of the last trending fetch
+    trending_fetched: Option<(String, i64)>,
+    pub change_window: ChangeWindow,
+    /// Show the coin list as a treemap instead of a table
+    pub treemap: bool,
+    /// Screen area of each treemap block, by visible row
+    pub treemap_areas: Vec<Rect>,
+    // Portfolio allocation panel
+    pub allocation_open: bool,
+    pub allocation_group: bool,
     pub settings_editing: bool,
     pub coingecko_api_key: String,
     pub cmc_api_key: String,
@@ -61,6 +139,14 @@ pub struct App {
     pub alert_flash: Option<(String, std::time::Instant)>,
     pub alert_input_buf: String,
     pub alert_direction: AlertDirection,
+    pub alert_kind: AlertKind,
+    /// Per-alert routing being edited; empty means the enabled sinks
+    pub alert_sinks: Vec<String>,
+    pub alert_sink_idx: usize,
+    pub alert_ntfy: AlertNtfy,
+    pub alert_field: AlertField,
+    // Volume/rank samples per coin for non-price alerts
+    pub market_history: HashMap<String, Vec<MarketSample>>,
     // Sort
     pub sort_column: Option<SortColumn>,
     pub sort_direction: SortDirection,
@@ -68,10 +154,16 @@ pub struct App {
     // Filter
     pub filter_query: String,
     // Notifications
-    pub notification_method: NotificationMethod,
-    pub ntfy_topic: String,
-    pub settings_notification_idx: usize,
-    pub settings_ntfy_topic: String,
+    pub notifications: NotificationSettings,
+    pub sinks: Vec<Arc<dyn NotificationSink>>,
+    pub settings_notifications: NotificationSettings,
+    pub settings_sink_idx: usize,
+    pub delivery_tx: UnboundedSender<DeliveryEvent>,
+    pub delivery_rx: UnboundedReceiver<DeliveryEvent>,
+    pub last_outbox_flush: Option<std::time::Instant>,
+    pub notification_test_pending: bool,
+    pub notification_test_results: Vec<(String, Option<String>)>,
+    pub last_digest_check: Option<std::time::Instant>,
     // Error timing
     pub error_time: Option<std::time::Instant>,
     // Buy price editing
@@ -85,9 +177,11 @@ impl App {
             .iter()
             .position(|t| *t == config.theme)
             .unwrap_or(0);
+        let (delivery_tx, delivery_rx) = mpsc::unbounded_channel();
         Self {
             tab: Tab::Markets,
             coins: Vec::new(),
+            markets_len: 0,
             selected: 0,
             scroll_offset: 0,
             page_height: 20,
@@ -95,6 +189,19 @@ impl App {
             chart_view: ChartView::Day1,
             chart_cache: HashMap::new(),
             loading_chart: false,
+            chart_synced: HashSet::new(),
+            chart_mode: ChartMode::Line,
+            chart_log: None,
+            chart_ma: MovingAverage::Off,
+            chart_indicators: ChartIndicators::default(),
+            coin_details: HashMap::new(),
+            details_synced: HashSet::new(),
+            compare_ids: Vec::new(),
+            compare_open: false,
+            ohlc_cache: HashMap::new(),
+            ohlc_synced: HashSet::new(),
+            chart_cursor: None,
+            chart_area: None,
             input_mode: InputMode::Password,
             input_buf: String::new(),
             db: None,
@@ -102,6 +209,8 @@ impl App {
             holdings: Vec::new(),
             last_refresh: None,
             last_refresh_display: String::new(),
+            markets_fetched_at: None,
+            stale_since: None,
             error: None,
             loading: true,
             config,
@@ -117,6 +226,23 @@ impl App {
             settings_cmc_key: String::new(),
             settings_currency_idx: 0,
             settings_theme_idx: theme_idx,
+            settings_markets_idx: 0,
+            settings_lazy_load: false,
+            markets_loaded: 0,
+            market_category: None,
+            sector_coins: Vec::new(),
+            categories: Vec::new(),
+            categories_fetched_at: None,
+            sector_query: String::new(),
+            sector_selected: 0,
+            sector_sort: SectorSort::MarketCap,
+            trending_coins: Vec::new(),
+            trending_fetched: None,
+            change_window: ChangeWindow::Day1,
+            treemap: false,
+            treemap_areas: Vec::new(),
+            allocation_open: false,
+            allocation_group: true,
             settings_editing: false,
             coingecko_api_key: String::new(),
             cmc_api_key: String::new(),
@@ -130,14 +256,26 @@ impl App {
             alert_flash: None,
             alert_input_buf: String::new(),
             alert_direction: AlertDirection::Above,
+            alert_kind: AlertKind::Price,
+            alert_sinks: Vec::new(),
+            alert_sink_idx: 0,
+            alert_ntfy: AlertNtfy::default(),
+            alert_field: AlertField::Target,
+            market_history: HashMap::new(),
             sort_column: None,
             sort_direction: SortDirection::Asc,
             sort_picking: false,
             filter_query: String::new(),
-            notification_method: NotificationMethod::None,
-            ntfy_topic: String::new(),
-            settings_notification_idx: 0,
-            settings_ntfy_topic: String::new(),
+            notifications: NotificationSettings::default(),
+            sinks: Vec::new(),
+            settings_notifications: NotificationSettings::default(),
+            settings_sink_idx: 0,
+            delivery_tx,
+            delivery_rx,
+            last_outbox_flush: None,
+            notification_test_pending: false,
+            notification_test_results: Vec::new(),
+            last_digest_check: None,
             error_time: None,
             buy_price_buf: String::new(),
         }
@@ -159,22 +297,15 @@ impl App {
         self.cmc_api_key = db_lock
             .get_setting("cmc_api_key")
             .unwrap_or_default();
-        self.notification_method = notification_method_from_str(
-            &db_lock.get_setting("notification_method").unwrap_or_default(),
-        );
-        self.ntfy_topic = db_lock
-            .get_setting("ntfy_topic")
-            .unwrap_or_default();
-        self.settings_notification_idx = NOTIFICATION_METHODS
-            .iter()
-            .position(|m| *m == notification_method_label(self.notification_method))
-            .unwrap_or(0);
+        self.notifications = NotificationSettings::load(db_lock);
+        self.rebuild_sinks();
         self.alerts = db_lock.get_alerts().unwrap_or_default();
     }
 
     pub fn visible_coins(&self) -> Vec<(usize, &Coin)> {
         let mut items: Vec<(usize, &Coin)> = match self.tab {
-            Tab::Markets => self.coins.iter().enumerate().collect(),
+            Tab::Markets if self.market_category.is_some() => self.sector_coins.iter().enumerate().collect(),
+            Tab::Markets => self.market_coins().iter().enumerate().collect(),
             Tab::Favourites => self
                 .coins
                 .iter()
@@ -190,19 +321,15 @@ impl App {
                 .enumerate()
                 .filter(|(_, c)| self.holdings.iter().any(|h| h.coin_id == c.id && h.amount > 0.0))
                 .collect(),
+            // Rows run through the panes in turn
+            Tab::Discover => self.discover_sections().into_iter().flatten().enumerate().collect(),
         };
 
         // Apply filter
-        if !self.filter_query.is_empty() {
-            let q = self.filter_query.to_lowercase();
-            items.retain(|(_, c)| {
-                c.name.to_lowercase().contains(&q)
-                    || c.symbol.to_lowercase().contains(&q)
-            });
-        }
+        items.retain(|(_, c)| self.matches_filter(c));
 
-        // Apply sort
-        if let Some(col) = self.sort_column {
+        // Apply sort; Discover keeps its own ordering
+        if let Some(col) = self.sort_column.filter(|_| self.tab != Tab::Discover) {
             let dir = self.sort_direction;
             items.sort_by(|(_, a), (_, b)| {
                 let cmp = match col {
@@ -225,11 +352,166 @@ impl App {
         items
     }
 
+    fn matches_filter(&self, coin: &Coin) -> bool {
+        if self.filter_query.is_empty() {
+            return true;
+        }
+        let q = self.filter_query.to_lowercase();
+        coin.name.to_lowercase().contains(&q) || coin.symbol.to_lowercase().contains(&q)
+    }
+
+    /// Trending coins, top gainers and top losers over `change_window`,
+    /// with gainers and losers drawn from the Markets list.
+    pub fn discover_sections(&self) -> [Vec<&Coin>; 3] {
+        let window = self.change_window;
+        let trending = self.trending_coins.iter().filter(|c| self.matches_filter(c)).collect();
+        let mut movers: Vec<(&Coin, f64)> = self
+            .market_coins()
+            .iter()
+            .filter(|c| self.matches_filter(c))
+            .filter_map(|c| window.change(c).map(|p| (c, p)))
+            .collect();
+        movers.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        let gainers = movers.iter().filter(|(_, p)| *p > 0.0).take(DISCOVER_MOVERS).map(|(c, _)| *c).collect();
+        let losers = movers.iter().rev().filter(|(_, p)| *p < 0.0).take(DISCOVER_MOVERS).map(|(c, _)| *c).collect();
+        [trending, gainers, losers]
+    }
+
+    /// Move the Discover selection to the first row of the next or previous
+    /// non-empty pane.
+    pub fn jump_discover_pane(&mut self, forward: bool) {
+        let mut starts = Vec::new();
+        let mut offset = 0;
+        for section in self.discover_sections() {
+            if !section.is_empty() {
+                starts.push(offset);
+            }
+            offset += section.len();
+        }
+        let current = starts.iter().rposition(|s| *s <= self.selected).unwrap_or(0);
+        let target = if forward {
+            starts.get(current + 1)
+        } else {
+            current.checked_sub(1).and_then(|i| starts.get(i))
+        };
+        if let Some(&start) = target {
+            self.selected = start;
+        }
+    }
+
+    pub fn market_coins(&self) -> &[Coin] {
+        &self.coins[..self.markets_len.min(self.coins.len())]
+    }
+
+    /// Add a coin picked from Discover or a sector after the Markets list, so
+    /// it shows on Favourites and Portfolio before the next refresh. From then
+    /// on `tracked_ids` keeps it loaded.
+    pub fn track_coin(&mut self, coin: Coin) {
+        if !self.coins.iter().any(|c| c.id == coin.id) {
+            self.coins.push(coin);
+        }
+    }
+
+    /// Favourites, holdings and coins with pending alerts outside the Markets
+    /// list, which are fetched separately on every refresh. Alerted coins
+    /// keep being sampled so a "leaves top N" rank alert can still fire.
+    fn tracked_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        let wanted = self
+            .favourites
+            .iter()
+            .chain(self.holdings.iter().filter(|h| h.amount > 0.0).map(|h| &h.coin_id))
+            .chain(self.alerts.iter().filter(|a| !a.triggered).map(|a| &a.coin_id));
+        for id in wanted {
+            if !ids.contains(id) && !self.market_coins().iter().any(|c| c.id == *id) {
+                ids.push(id.clone());
+            }
+        }
+        ids
+    }
+
+    /// Refetch the tracked coins after the Markets list. On failure the
+    /// previous copies in `stale` are kept.
+    async fn refresh_tracked_coins(&mut self, client: &CoinGeckoClient, stale: Vec<Coin>) {
+        let ids = self.tracked_ids();
+        self.coins.truncate(self.markets_len);
+        if ids.is_empty() {
+            return;
+        }
+        match client.fetch_coins_market(&ids).await {
+            Ok(coins) => {
+                self.coins.extend(coins);
+                self.save_snapshot(&format!("tracked:{}", self.config.currency), &self.coins[self.markets_len..])
+                    .await;
+            }
+            Err(e) => {
+                self.set_error(format!("Tracked coins: {}", e));
+                self.coins.extend(stale.into_iter().filter(|c| ids.contains(&c.id)));
+            }
+        }
+    }
+
+    /// Load the trending list from the local snapshot, refreshing it from the
+    /// API once it is older than ten minutes or the currency has changed.
+    pub async fn sync_trending(&mut self, client: &CoinGeckoClient) {
+        let now = chrono::Utc::now().timestamp();
+        let currency = self.config.currency.clone();
+        match self.trending_fetched {
+            Some((ref c, t)) if *c == currency && now - t < TRENDING_MAX_AGE_SECS => return,
+            Some((ref c, _)) if *c == currency => {}
+            _ => {
+                self.trending_coins.clear();
+                let kind = format!("trending:{}", currency);
+                let cached = match self.db {
+                    Some(ref db) => db.lock().await.get_snapshot(&kind),
+                    None => None,
+                };
+                if let Some((payload, fetched_at)) = cached {
+                    if let Ok(coins) = serde_json::from_str(&payload) {
+                        self.trending_coins = coins;
+                        self.trending_fetched = Some((currency.clone(), fetched_at));
+                        if now - fetched_at < TRENDING_MAX_AGE_SECS {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+        // Don't retry a failed fetch on every tick
+        self.trending_fetched = Some((currency.clone(), now));
+        let result = match client.fetch_trending().await {
+            Ok(ids) if ids.is_empty() => Ok(Vec::new()),
+            Ok(ids) => client.fetch_coins_market(&ids).await.map(|mut coins| {
+                coins.sort_by_key(|c| ids.iter().position(|id| *id == c.id));
+                coins
+            }),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(coins) => {
+                self.save_snapshot(&format!("trending:{}", currency), &coins).await;
+                self.trending_coins = coins;
+            }
+            Err(e) => self.set_error(format!("Trending: {}", e)),
+        }
+    }
+
     pub fn selected_coin(&self) -> Option<&Coin> {
         let visible = self.visible_coins();
         visible.get(self.selected).map(|(_, c)| *c)
     }
 
+    /// The nearest coin after (or before) the selection that has a block in
+    /// the treemap; coins too small to draw are skipped.
+    pub fn next_treemap_block(&self, forward: bool) -> Option<usize> {
+        let drawn = |i: &usize| self.treemap_areas.get(*i).is_some_and(|r| !r.is_empty());
+        if forward {
+            (self.selected + 1..self.treemap_areas.len()).find(drawn)
+        } else {
+            (0..self.selected).rev().find(drawn)
+        }
+    }
+
     pub fn clamp_selection(&mut self) {
         let len = self.visible_coins().len();
         if len == 0 {
@@ -263,6 +545,73 @@ impl App {
             .sum()
     }
 
+    /// Value of each holding with a known price, largest first.
+    fn holding_values(&self) -> Vec<(&Coin, f64)> {
+        let mut values: Vec<(&Coin, f64)> = self
+            .holdings
+            .iter()
+            .filter_map(|h| {
+                let coin = self.coins.iter().find(|c| c.id == h.coin_id)?;
+                Some((coin, coin.current_price * h.amount))
+            })
+            .filter(|(_, v)| *v > 0.0)
+            .collect();
+        values.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        values
+    }
+
+    /// Value per coin, largest first. With `allocation_group` on, two or more
+    /// positions under `ALLOCATION_OTHER_PCT` are merged into a final "Other".
+    pub fn allocation_by_coin(&self) -> Vec<(String, f64)> {
+        let values = self.holding_values();
+        let total: f64 = values.iter().map(|(_, v)| v).sum();
+        let is_small = |v: f64| self.allocation_group && v / total * 100.0 < ALLOCATION_OTHER_PCT;
+        let small = values.iter().filter(|(_, v)| is_small(*v)).count();
+        let mut out = Vec::new();
+        let mut other = 0.0;
+        for (coin, value) in &values {
+            if small >= 2 && is_small(*value) {
+                other += value;
+            } else {
+                out.push((coin.symbol.to_uppercase(), *value));
+            }
+        }
+        if other > 0.0 {
+            out.push((format!("Other ({})", small), other));
+        }
+        out
+    }
+
+    /// Value per asset class, in `AssetClass::ALL` order.
+    pub fn allocation_by_class(&self) -> Vec<(AssetClass, f64)> {
+        let values = self.holding_values();
+        AssetClass::ALL
+            .iter()
+            .map(|class| {
+                let sum = values
+                    .iter()
+                    .filter(|(c, _)| self.asset_class(c) == *class)
+                    .map(|(_, v)| v)
+                    .sum();
+                (*class, sum)
+            })
+            .collect()
+    }
+
+    fn asset_class(&self, coin: &Coin) -> AssetClass {
+        let listed_stable = self
+            .coin_details
+            .get(&coin.id)
+            .is_some_and(|d| d.categories.iter().any(|c| c == "Stablecoins"));
+        if listed_stable || STABLECOIN_IDS.contains(&coin.id.as_str()) {
+            AssetClass::Stablecoin
+        } else if coin.market_cap_rank.is_some_and(|r| r <= MAJOR_RANK) {
+            AssetClass::Major
+        } else {
+            AssetClass::Alt
+        }
+    }
+
     pub fn holding_for(&self, coin_id: &str) -> f64 {
         self.holdings
             .iter()
@@ -303,18 +652,155 @@ impl App {
         }
     }
 
+    /// Show the last markets and global stats saved for this currency until
+    /// fresh data arrives.
+    pub async fn load_snapshot(&mut self) {
+        let Some(ref db) = self.db else { return };
+        let db = db.lock().await;
+        if let Some((payload, fetched_at)) = db.get_snapshot(&format!("markets:{}", self.config.currency)) {
+            if let Ok(coins) = serde_json::from_str::<Vec<Coin>>(&payload) {
+                self.coins = coins;
+                self.markets_len = self.coins.len();
+                self.markets_fetched_at = Some(fetched_at);
+                self.stale_since = Some(fetched_at);
+            }
+        }
+        if let Some((payload, _)) = db.get_snapshot(&format!("tracked:{}", self.config.currency)) {
+            if let Ok(tracked) = serde_json::from_str::<Vec<Coin>>(&payload) {
+                for coin in tracked {
+                    if !self.coins.iter().any(|c| c.id == coin.id) {
+                        self.coins.push(coin);
+                    }
+                }
+            }
+        }
+        if let Some((payload, _)) = db.get_snapshot("global") {
+            self.global_stats = serde_json::from_str(&payload).ok();
+        }
+    }
+
+    async fn save_snapshot<T: serde::Serialize + ?Sized>(&self, kind: &str, data: &T) {
+        let Some(ref db) = self.db else { return };
+        let saved = match serde_json::to_string(data) {
+            Ok(payload) => db
+                .lock()
+                .await
+                .set_snapshot(kind, &payload, chrono::Utc::now().timestamp()),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = saved {
+            log_error(&format!("Snapshot {}: {}", kind, e));
+        }
+    }
+
     pub async fn refresh_market_data(&mut self, client: &CoinGeckoClient) {
-        match client.fetch_markets(50).await {
+        let limit = self.config.markets_limit.max(1);
+        // Lazy loading refreshes the pages seen so far, starting with one
+        self.markets_loaded = if self.config.lazy_load {
+            self.markets_loaded.clamp(LAZY_PAGE_SIZE.min(limit), limit)
+        } else {
+            limit
+        };
+        match client.fetch_markets(self.markets_loaded, None).await {
             Ok(coins) => {
+                let stale = self.coins.split_off(self.markets_len.min(self.coins.len()));
                 self.coins = coins;
+                self.markets_len = self.coins.len();
                 self.last_refresh = Some(std::time::Instant::now());
                 self.error = None;
                 self.loading = false;
+                self.stale_since = None;
+                self.markets_fetched_at = Some(chrono::Utc::now().timestamp());
+                self.save_snapshot(&format!("markets:{}", self.config.currency), &self.coins)
+                    .await;
+                self.refresh_tracked_coins(client, stale).await;
+                self.record_market_history().await;
             }
             Err(e) => {
                 self.set_error(format!("API: {}", e));
                 self.loading = false;
+                // Keep showing what we have and retry on the normal interval
+                self.last_refresh = Some(std::time::Instant::now());
+                self.stale_since = self.markets_fetched_at;
+            }
+        }
+        if self.market_category.is_some() {
+            self.refresh_sector_coins(client).await;
+        }
+    }
+
+    async fn refresh_sector_coins(&mut self, client: &CoinGeckoClient) {
+        let Some(id) = self.market_category.as_ref().map(|c| c.id.clone()) else { return };
+        match client.fetch_markets(self.config.markets_limit.max(1), Some(&id)).await {
+            Ok(coins) => self.sector_coins = coins,
+            Err(e) => self.set_error(format!("Sector: {}", e)),
+        }
+    }
+
+    /// Narrow the Markets tab to one sector, or back to all coins with `None`.
+    pub async fn set_market_category(&mut self, category: Option<MarketCategory>, client: &CoinGeckoClient) {
+        self.market_category = category;
+        self.sector_coins.clear();
+        self.tab = Tab::Markets;
+        self.selected = 0;
+        self.scroll_offset = 0;
+        if self.market_category.is_some() {
+            self.loading = true;
+            self.refresh_sector_coins(client).await;
+            self.loading = false;
+        }
+        self.clamp_selection();
+    }
+
+    /// Categories matching the overview filter, in the chosen order.
+    pub fn visible_sectors(&self) -> Vec<&MarketCategory> {
+        let q = self.sector_query.to_lowercase();
+        let mut items: Vec<&MarketCategory> = self
+            .categories
+            .iter()
+            .filter(|c| q.is_empty() || c.name.to_lowercase().contains(&q))
+            .collect();
+        let sort = self.sector_sort;
+        items.sort_by(|a, b| sort.key(b).partial_cmp(&sort.key(a)).unwrap_or(std::cmp::Ordering::Equal));
+        items
+    }
+
+    pub fn open_sectors(&mut self) {
+        self.sector_query.clear();
+        self.sector_selected = 0;
+        self.input_mode = InputMode::Sectors;
+    }
+
+    /// Load the sector totals from the local snapshot, refreshing them from
+    /// the API once they are older than five minutes.
+    pub async fn sync_categories(&mut self, client: &CoinGeckoClient) {
+        let now = chrono::Utc::now().timestamp();
+        if self.categories_fetched_at.is_some_and(|t| now - t < CATEGORIES_MAX_AGE_SECS) {
+            return;
+        }
+        if self.categories.is_empty() {
+            let cached = match self.db {
+                Some(ref db) => db.lock().await.get_snapshot("categories"),
+                None => None,
+            };
+            if let Some((payload, fetched_at)) = cached {
+                if let Ok(categories) = serde_json::from_str(&payload) {
+                    self.categories = categories;
+                    self.categories_fetched_at = Some(fetched_at);
+                    if now - fetched_at < CATEGORIES_MAX_AGE_SECS {
+                        return;
+                    }
+                }
+            }
+        }
+        // Don't retry a failed fetch on every tick
+        self.categories_fetched_at = Some(now);
+        match client.fetch_categories().await {
+            Ok(categories) => {
+                self.save_snapshot("categories", &categories).await;
+                self.categories = categories;
             }
+            Err(e) => self.set_error(format!("Sectors: {}", e)),
         }
     }
 
@@ -322,8 +808,87 @@ impl App {
         match self.settings_field {
             SettingsField::CoingeckoApiKey => &mut self.settings_coingecko_key,
             SettingsField::CoinmarketcapApiKey => &mut self.settings_cmc_key,
-            SettingsField::NtfyTopic => &mut self.settings_ntfy_topic,
-            SettingsField::Currency | SettingsField::Theme | SettingsField::Notifications => &mut self.settings_coingecko_key, // unused for cycle fields
+            SettingsField::NtfyServer => &mut self.settings_notifications.ntfy.server,
+            SettingsField::NtfyTopic => &mut self.settings_notifications.ntfy.topic,
+            SettingsField::NtfyToken => &mut self.settings_notifications.ntfy.token,
+            SettingsField::NtfyUsername => &mut self.settings_notifications.ntfy.username,
+            SettingsField::NtfyPassword => &mut self.settings_notifications.ntfy.password,
+            SettingsField::NtfyTags => &mut self.settings_notifications.ntfy.tags,
+            SettingsField::NtfyClickUrl => &mut self.settings_notifications.ntfy.click_url,
+            SettingsField::WebhookUrl => &mut self.settings_notifications.webhook.url,
+            SettingsField::WebhookHeaders => &mut self.settings_notifications.webhook.headers,
+            SettingsField::WebhookTemplate => &mut self.settings_notifications.webhook.body_template,
+            SettingsField::SmtpHost => &mut self.settings_notifications.smtp.host,
+            SettingsField::SmtpPort => &mut self.settings_notifications.smtp.port,
+            SettingsField::SmtpUsername => &mut self.settings_notifications.smtp.username,
+            SettingsField::SmtpPassword => &mut self.settings_notifications.smtp.password,
+            SettingsField::SmtpFrom => &mut self.settings_notifications.smtp.from,
+            SettingsField::SmtpTo => &mut self.settings_notifications.smtp.to,
+            SettingsField::TelegramApiUrl => &mut self.settings_notifications.telegram.api_url,
+            SettingsField::TelegramToken => &mut self.settings_notifications.telegram.token,
+            SettingsField::TelegramChatId => &mut self.settings_notifications.telegram.chat_id,
+            SettingsField::DiscordWebhookUrl => &mut self.settings_notifications.discord_url,
+            SettingsField::SlackWebhookUrl => &mut self.settings_notifications.slack_url,
+            SettingsField::ExecCommand => &mut self.settings_notifications.exec.command,
+            SettingsField::ExecTimeout => &mut self.settings_notifications.exec.timeout_secs,
+            SettingsField::QuietStart => &mut self.settings_notifications.quiet.start,
+            SettingsField::QuietEnd => &mut self.settings_notifications.quiet.end,
+            SettingsField::TemplateTitle => &mut self.settings_notifications.templates.title,
+            SettingsField::TemplateBody => &mut self.settings_notifications.templates.body,
+            SettingsField::Currency
+            | SettingsField::Theme
+            | SettingsField::MarketsLimit
+            | SettingsField::LazyLoad
+            | SettingsField::Notifications
+            | SettingsField::NtfyPriority
+            | SettingsField::WebhookMethod
+            | SettingsField::SmtpSecurity
+            | SettingsField::QuietMode
+            | SettingsField::TestNotification => &mut self.settings_coingecko_key, // unused for cycle fields
+        }
+    }
+
+    pub fn settings_text_value(&self, field: SettingsField) -> &str {
+        match field {
+            SettingsField::CoingeckoApiKey => &self.settings_coingecko_key,
+            SettingsField::CoinmarketcapApiKey => &self.settings_cmc_key,
+            SettingsField::NtfyServer => &self.settings_notifications.ntfy.server,
+            SettingsField::NtfyTopic => &self.settings_notifications.ntfy.topic,
+            SettingsField::NtfyToken => &self.settings_notifications.ntfy.token,
+            SettingsField::NtfyUsername => &self.settings_notifications.ntfy.username,
+            SettingsField::NtfyPassword => &self.settings_notifications.ntfy.password,
+            SettingsField::NtfyTags => &self.settings_notifications.ntfy.tags,
+            SettingsField::NtfyClickUrl => &self.settings_notifications.ntfy.click_url,
+            SettingsField::WebhookUrl => &self.settings_notifications.webhook.url,
+            SettingsField::WebhookHeaders => &self.settings_notifications.webhook.headers,
+            SettingsField::WebhookTemplate => &self.settings_notifications.webhook.body_template,
+            SettingsField::SmtpHost => &self.settings_notifications.smtp.host,
+            SettingsField::SmtpPort => &self.settings_notifications.smtp.port,
+            SettingsField::SmtpUsername => &self.settings_notifications.smtp.username,
+            SettingsField::SmtpPassword => &self.settings_notifications.smtp.password,
+            SettingsField::SmtpFrom => &self.settings_notifications.smtp.from,
+            SettingsField::SmtpTo => &self.settings_notifications.smtp.to,
+            SettingsField::TelegramApiUrl => &self.settings_notifications.telegram.api_url,
+            SettingsField::TelegramToken => &self.settings_notifications.telegram.token,
+            SettingsField::TelegramChatId => &self.settings_notifications.telegram.chat_id,
+            SettingsField::DiscordWebhookUrl => &self.settings_notifications.discord_url,
+            SettingsField::SlackWebhookUrl => &self.settings_notifications.slack_url,
+            SettingsField::ExecCommand => &self.settings_notifications.exec.command,
+            SettingsField::ExecTimeout => &self.settings_notifications.exec.timeout_secs,
+            SettingsField::QuietStart => &self.settings_notifications.quiet.start,
+            SettingsField::QuietEnd => &self.settings_notifications.quiet.end,
+            SettingsField::TemplateTitle => &self.settings_notifications.templates.title,
+            SettingsField::TemplateBody => &self.settings_notifications.templates.body,
+            SettingsField::Currency
+            | SettingsField::Theme
+            | SettingsField::MarketsLimit
+            | SettingsField::LazyLoad
+            | SettingsField::Notifications
+            | SettingsField::NtfyPriority
+            | SettingsField::WebhookMethod
+            | SettingsField::SmtpSecurity
+            | SettingsField::QuietMode
+            | SettingsField::TestNotification => "",
         }
     }
 
@@ -336,6 +901,15 @@ impl App {
         }
     }
 
+    pub fn cycle_markets_limit(&mut self, forward: bool) {
+        let len = MARKET_LIMITS.len();
+        if forward {
+            self.settings_markets_idx = (self.settings_markets_idx + 1) % len;
+        } else {
+            self.settings_markets_idx = (self.settings_markets_idx + len - 1) % len;
+        }
+    }
+
     pub fn cycle_theme(&mut self, forward: bool) {
         let len = THEME_NAMES.len();
         if forward {
@@ -349,9 +923,14 @@ impl App {
 
     pub fn set_error(&mut self, msg: String) {
         log_error(&msg);
+        self.show_error(msg);
+    }
+
+    /// Put `msg` on the status line without logging it.
+    pub fn show_error(&mut self, msg: String) {
         // Truncate display to 80 chars
-        let display = if msg.len() > 80 {
-            format!("{}...", &msg[..77])
+        let display = if msg.chars().count() > 80 {
+            format!("{}...", msg.chars().take(77).collect::<String>())
         } else {
             msg
         };
@@ -359,42 +938,430 @@ impl App {
         self.error_time = Some(std::time::Instant::now());
     }
 
-    pub fn check_alerts(&mut self) {
+    pub async fn load_market_history(&mut self) {
+        if let Some(ref db) = self.db {
+            let db = db.lock().await;
+            let since = chrono::Utc::now().timestamp() - MARKET_HISTORY_SECS;
+            self.market_history = db.get_market_history(since).unwrap_or_default();
+        }
+    }
+
+    /// Append the current volume/rank of every listed coin to the history,
+    /// dropping samples that have aged out of the window.
+    async fn record_market_history(&mut self) {
+        let now = chrono::Utc::now().timestamp();
+        let cutoff = now - MARKET_HISTORY_SECS;
+        for coin in &self.coins {
+            let samples = self.market_history.entry(coin.id.clone()).or_default();
+            samples.retain(|s| s.recorded_at >= cutoff);
+            samples.push(MarketSample {
+                recorded_at: now,
+                total_volume: coin.total_volume,
+                market_cap_rank: coin.market_cap_rank,
+            });
+        }
+        self.market_history.retain(|_, samples| samples.iter().any(|s| s.recorded_at >= cutoff));
+        if let Some(ref db) = self.db {
+            let db = db.lock().await;
+            if let Err(e) = db.add_market_samples(&self.coins, now) {
+                log_error(&format!("Market history: {}", e));
+            }
+            let _ = db.prune_market_history(cutoff);
+        }
+    }
+
+    /// Evaluate pending alerts against the latest fetch. Skipped while the
+    /// list is a stale snapshot so old prices can't fire alerts.
+    pub async fn check_alerts(&mut self) {
+        if self.stale_since.is_some() {
+            return;
+        }
         for alert in &mut self.alerts {
             if alert.triggered {
                 continue;
             }
             if let Some(coin) = self.coins.iter().find(|c| c.id == alert.coin_id) {
-                let triggered = match alert.direction {
-                    AlertDirection::Above => coin.current_price >= alert.target_price,
-                    AlertDirection::Below => coin.current_price <= alert.target_price,
-                };
-                if triggered {
+                // The latest sample is the current refresh; compare against the ones before it
+                let prior = self
+                    .market_history
+                    .get(&coin.id)
+                    .and_then(|s| s.split_last())
+                    .map(|(_, rest)| rest)
+                    .unwrap_or(&[]);
+                if let Some(observed) = evaluate_alert(alert, coin, prior) {
+                    let now = chrono::Utc::now().timestamp();
                     alert.triggered = true;
+                    alert.triggered_at = Some(now);
+                    alert.trigger_price = Some(coin.current_price);
                     // Terminal bell
                     print!("\x07");
                     // Flash
                     self.alert_flash = Some((alert.coin_id.clone(), std::time::Instant::now()));
-                    // Send notification
-                    notifications::send_alert(
-                        self.notification_method,
-                        &self.ntfy_topic,
-                        &coin.name,
-                        alert.target_price,
-                        coin.current_price,
-                        alert.direction,
-                    );
-                    // Mark in DB
+                    // Record the trigger before notifying so a restart can't refire it
                     if let Some(ref db) = self.db {
-                        if let Ok(db) = db.try_lock() {
-                            let _ = db.mark_alert_triggered(&alert.coin_id, alert.target_price);
+                        let _ = db.lock().await.mark_alert_triggered(alert.id, coin.current_price, now);
+                        notifications::send_alert(
+                            db.clone(),
+                            &self.notifications.route(&self.sinks, &alert.sinks),
+                            AlertMessage::for_alert(
+                                alert,
+                                coin,
+                                observed,
+                                &self.config.currency,
+                                &self.notifications.templates,
+                            ),
+                            &self.notifications.quiet,
+                            self.delivery_tx.clone(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Retry due outbox entries every `OUTBOX_FLUSH_SECS`.
+    pub fn flush_outbox_if_due(&mut self) {
+        let due = self
+            .last_outbox_flush
+            .is_none_or(|t| t.elapsed().as_secs() >= OUTBOX_FLUSH_SECS);
+        if !due {
+            return;
+        }
+        if let Some(ref db) = self.db {
+            self.last_outbox_flush = Some(std::time::Instant::now());
+            notifications::flush_outbox(db.clone(), &self.sinks, self.delivery_tx.clone());
+        }
+    }
+
+    /// Send the portfolio digest when its scheduled time has passed.
+    pub async fn check_digest(&mut self) {
+        let due = self
+            .last_digest_check
+            .is_none_or(|t| t.elapsed().as_secs() >= DIGEST_CHECK_SECS);
+        // Offline snapshot prices would skew the digest and its baseline
+        if !due || self.coins.is_empty() || self.stale_since.is_some() {
+            return;
+        }
+        self.last_digest_check = Some(std::time::Instant::now());
+        let Some(ref db) = self.db else { return };
+        let now = chrono::Utc::now();
+        let guard = db.lock().await;
+        let msg = match digest::state(&guard, &self.config.digest, now) {
+            Some(DigestState::Due) => Some(digest::build(
+                &guard,
+                &self.holdings,
+                &self.coins,
+                &self.alerts,
+                &self.config.currency,
+            )),
+            Some(DigestState::Baseline) => None,
+            _ => return,
+        };
+        if let Err(e) = digest::record(&guard, &self.holdings, &self.coins, now) {
+            log_error(&format!("Digest: {}", e));
+        }
+        drop(guard);
+        if let Some(msg) = msg {
+            notifications::send_alert(
+                db.clone(),
+                &self.notifications.route(&self.sinks, &[]),
+                msg,
+                &self.notifications.quiet,
+                self.delivery_tx.clone(),
+            );
+        }
+    }
+
+    /// Apply results from background notification deliveries.
+    pub fn poll_deliveries(&mut self) {
+        while let Ok(event) = self.delivery_rx.try_recv() {
+            match event {
+                // Already logged by the outbox
+                DeliveryEvent::Failed { sink, error } => {
+                    self.show_error(format!("Notification via {} failed: {}", sink, error));
+                }
+                DeliveryEvent::TestFinished(results) => {
+                    for (sink, err) in &results {
+                        if let Some(err) = err {
+                            log_error(&format!("Test notification via {}: {}", sink, err));
                         }
                     }
+                    self.notification_test_pending = false;
+                    self.notification_test_results = results;
                 }
             }
         }
     }
 
+    /// Rebuild the notification sinks from the saved settings.
+    pub fn rebuild_sinks(&mut self) {
+        self.sinks = match self.notifications.build_all_sinks() {
+            Ok(sinks) => sinks,
+            Err(e) => {
+                self.set_error(format!("Notifications: {:#}", e));
+                Vec::new()
+            }
+        };
+    }
+
+    /// Send a test message through the sinks as currently configured in the
+    /// settings dialog, before they are saved.
+    pub fn send_test_notification(&mut self) {
+        self.notification_test_results.clear();
+        let sinks = match self.settings_notifications.build_sinks() {
+            Ok(sinks) => sinks,
+            Err(e) => {
+                self.notification_test_results.push(("none".to_string(), Some(format!("{:#}", e))));
+                return;
+            }
+        };
+        if sinks.is_empty() {
+            self.notification_test_results
+                .push(("none".to_string(), Some("no sinks enabled and configured".to_string())));
+            return;
+        }
+        self.notification_test_pending = true;
+        notifications::send_test(sinks, self.delivery_tx.clone());
+    }
+
+    fn chart_key(&self) -> Option<(String, u32)> {
+        self.selected_coin()
+            .map(|c| (c.id.clone(), self.chart_view.days()))
+    }
+
+    pub fn chart_log_scale(&self) -> bool {
+        self.chart_log.unwrap_or(self.chart_view.is_long())
+    }
+
+    pub fn toggle_chart_log(&mut self) {
+        self.chart_log = Some(!self.chart_log_scale());
+    }
+
+    /// Charts the open popup needs: the selected coin, or every compared one.
+    fn chart_keys(&self) -> Vec<(String, u32)> {
+        if self.compare_open {
+            let days = self.chart_view.days();
+            self.compare_ids.iter().map(|id| (id.clone(), days)).collect()
+        } else {
+            self.chart_key().into_iter().collect()
+        }
+    }
+
+    /// Add the selected coin to the compare set, or take it out again.
+    pub fn toggle_compare(&mut self) {
+        let Some(id) = self.selected_coin().map(|c| c.id.clone()) else { return };
+        if let Some(pos) = self.compare_ids.iter().position(|c| *c == id) {
+            self.compare_ids.remove(pos);
+        } else {
+            self.compare_ids.push(id);
+        }
+    }
+
+    pub fn current_chart(&self) -> Option<&PriceHistory> {
+        self.chart_key().and_then(|key| self.chart_cache.get(&key))
+    }
+
+    /// Move the inspect cursor by about one screen column.
+    pub fn move_chart_cursor(&mut self, forward: bool) {
+        let width = self.chart_area.map(|a| a.width as usize).unwrap_or(1).max(1);
+        let Some(len) = self.current_chart().map(|h| h.prices.len()) else { return };
+        if len == 0 {
+            return;
+        }
+        let step = (len / width).max(1);
+        let at = self.chart_cursor.unwrap_or(len - 1).min(len - 1);
+        self.chart_cursor = Some(if forward {
+            (at + step).min(len - 1)
+        } else {
+            at.saturating_sub(step)
+        });
+    }
+
+    /// Put the inspect cursor on the point under screen column `x`.
+    pub fn set_chart_cursor_at(&mut self, x: u16) {
+        let Some(area) = self.chart_area else { return };
+        let Some(history) = self.current_chart() else { return };
+        let (Some(&t0), Some(&t1)) = (history.timestamps.first(), history.timestamps.last()) else {
+            return;
+        };
+        let col = x.saturating_sub(area.x).min(area.width.saturating_sub(1)) as f64;
+        let frac = col / (area.width.saturating_sub(1).max(1)) as f64;
+        let ts = t0 + ((t1 - t0) as f64 * frac).round() as i64;
+        self.chart_cursor = history.nearest(ts);
+    }
+
+    /// Show the open charts from the local cache, where there is one. The
+    /// network sync happens separately in `sync_chart`.
+    pub async fn load_cached_chart(&mut self) {
+        for key in self.chart_keys() {
+            if self.chart_cache.contains_key(&key) {
+                continue;
+            }
+            // Not shown yet, so let a failed or never-run sync try again
+            self.chart_synced.remove(&key);
+            let Some(ref db) = self.db else { return };
+            let window_start = self.chart_view.window_start(chrono::Utc::now().timestamp());
+            let points = db
+                .lock()
+                .await
+                .get_price_history(&key.0, &self.config.currency, key.1)
+                .unwrap_or_default();
+            let points: Vec<PricePoint> = points
+                .into_iter()
+                .filter(|p| p.ts >= window_start)
+                .collect();
+            if points.is_empty() {
+                self.loading_chart = true;
+            } else {
+                self.chart_cache.insert(key, PriceHistory::from_points(&points));
+            }
+        }
+    }
+
+    /// Bring one open chart up to date per call, once per session, fetching
+    /// only the points after the cached ones when the cache still overlaps
+    /// the range. One at a time so compare mode redraws between fetches.
+    pub async fn sync_chart(&mut self, client: &CoinGeckoClient) {
+        let Some(key) = self
+            .chart_keys()
+            .into_iter()
+            .find(|k| !self.chart_synced.contains(k))
+        else {
+            return;
+        };
+        self.chart_synced.insert(key.clone());
+        let Some(db) = self.db.clone() else { return };
+        let (coin_id, days) = (key.0.clone(), key.1);
+        let now = chrono::Utc::now().timestamp();
+        let window_start = self.chart_view.window_start(now);
+        let step = self.chart_view.step_secs();
+
+        let mut cached = db
+            .lock()
+            .await
+            .get_price_history(&coin_id, &self.config.currency, days)
+            .unwrap_or_default();
+        cached.retain(|p| p.ts >= window_start);
+
+        let fetched = match cached.last().map(|p| p.ts) {
+            Some(last) if now - last < step => Ok(cached),
+            Some(last) => client
+                .fetch_price_range(&coin_id, last + 1, now)
+                .await
+                .map(|tail| merge_price_tail(cached, tail, step)),
+            None => client.fetch_price_history(&coin_id, &self.chart_view.api_days()).await,
+        };
+        self.loading_chart = false;
+
+        match fetched {
+            Ok(points) => {
+                if let Err(e) = db
+                    .lock()
+                    .await
+                    .set_price_history(&coin_id, &self.config.currency, days, &points)
+                {
+                    log_error(&format!("Chart cache: {}", e));
+                }
+                self.chart_cache.insert(key, PriceHistory::from_points(&points));
+            }
+            Err(e) => self.set_error(format!("Chart: {}", e)),
+        }
+    }
+
+    /// Whether more Markets pages can be fetched by scrolling.
+    pub fn can_load_more(&self) -> bool {
+        self.config.lazy_load
+            && self.tab == Tab::Markets
+            && self.market_category.is_none()
+            && self.markets_loaded < self.config.markets_limit
+    }
+
+    /// Append the next page of the Markets tab when lazy loading.
+    pub async fn load_more_markets(&mut self, client: &CoinGeckoClient) {
+        if !self.can_load_more() || self.loading {
+            return;
+        }
+        let page = self.markets_loaded / LAZY_PAGE_SIZE + 1;
+        self.loading = true;
+        match client.fetch_markets_page(LAZY_PAGE_SIZE, page, None).await {
+            Ok(batch) => {
+                let full = batch.len() as u32 == LAZY_PAGE_SIZE;
+                for coin in batch {
+                    if self.market_coins().iter().any(|c| c.id == coin.id) {
+                        continue;
+                    }
+                    // A tracked coin reached by scrolling moves into the list
+                    if let Some(i) = self.coins.iter().position(|c| c.id == coin.id) {
+                        self.coins.remove(i);
+                    }
+                    self.coins.insert(self.markets_len, coin);
+                    self.markets_len += 1;
+                }
+                // A short page means CoinGecko has no more coins
+                self.markets_loaded = if full {
+                    (self.markets_loaded + LAZY_PAGE_SIZE).min(self.config.markets_limit)
+                } else {
+                    self.config.markets_limit
+                };
+                self.save_snapshot(&format!("markets:{}", self.config.currency), self.market_coins())
+                    .await;
+            }
+            Err(e) => self.set_error(format!("API: {}", e)),
+        }
+        self.loading = false;
+    }
+
+    /// Load the selected coin's profile from the local snapshot, refreshing it
+    /// from the API when it is older than a day. Once per session.
+    pub async fn sync_details(&mut self, client: &CoinGeckoClient) {
+        let Some(id) = self.selected_coin().map(|c| c.id.clone()) else { return };
+        if !self.details_synced.insert(id.clone()) {
+            return;
+        }
+        let kind = format!("details:{}", id);
+        let now = chrono::Utc::now().timestamp();
+        let cached = match self.db {
+            Some(ref db) => db.lock().await.get_snapshot(&kind),
+            None => None,
+        };
+        let mut fresh = false;
+        if let Some((payload, fetched_at)) = cached {
+            if let Ok(details) = serde_json::from_str::<CoinDetails>(&payload) {
+                self.coin_details.insert(id.clone(), details);
+                fresh = now - fetched_at < DETAILS_MAX_AGE_SECS;
+            }
+        }
+        if fresh {
+            return;
+        }
+        match client.fetch_coin_details(&id).await {
+            Ok(details) => {
+                self.save_snapshot(&kind, &details).await;
+                self.coin_details.insert(id, details);
+            }
+            // A stale profile is still worth showing
+            Err(_) if self.coin_details.contains_key(&id) => {}
+            Err(e) => self.set_error(format!("Details: {}", e)),
+        }
+    }
+
+    /// Fetch candles for the selected chart once per session while candle mode is on.
+    pub async fn sync_candles(&mut self, client: &CoinGeckoClient) {
+        if self.chart_mode != ChartMode::Candles {
+            return;
+        }
+        let Some(key) = self.chart_key() else { return };
+        if !self.ohlc_synced.insert(key.clone()) {
+            return;
+        }
+        match client.fetch_ohlc(&key.0, &self.chart_view.api_days()).await {
+            Ok(candles) => {
+                self.ohlc_cache.insert(key, candles);
+            }
+            Err(e) => self.set_error(format!("Candles: {}", e)),
+        }
+    }
+
     pub async fn refresh_alerts(&mut self) {
         if let Some(ref db) = self.db {
             let db = db.lock().await;
@@ -410,6 +1377,7 @@ impl App {
                     stats.fear_greed_index = Some(index);
                     stats.fear_greed_label = Some(label);
                 }
+                self.save_snapshot("global", &stats).await;
                 self.global_stats = Some(stats);
             }
             Err(_) => {
@@ -429,25 +1397,96 @@ impl App {
             .iter()
             .position(|t| *t == self.config.theme)
             .unwrap_or(0);
-        self.settings_notification_idx = NOTIFICATION_METHODS
+        self.settings_markets_idx = MARKET_LIMITS
             .iter()
-            .position(|m| *m == notification_method_label(self.notification_method))
+            .position(|l| *l == self.config.markets_limit)
             .unwrap_or(0);
-        self.settings_ntfy_topic = self.ntfy_topic.clone();
+        self.settings_lazy_load = self.config.lazy_load;
+        self.settings_notifications = self.notifications.clone();
+        self.settings_sink_idx = 0;
+        self.notification_test_results.clear();
         self.settings_field = SettingsField::Currency;
         self.settings_editing = false;
         self.input_mode = InputMode::Settings;
     }
 
+    /// Move the sink cursor in the Notifications field.
     pub fn cycle_notification(&mut self, forward: bool) {
-        let len = NOTIFICATION_METHODS.len();
+        let len = NOTIFICATION_SINKS.len();
         if forward {
-            self.settings_notification_idx = (self.settings_notification_idx + 1) % len;
+            self.settings_sink_idx = (self.settings_sink_idx + 1) % len;
+        } else {
+            self.settings_sink_idx = (self.settings_sink_idx + len - 1) % len;
+        }
+    }
+
+    pub fn toggle_selected_sink(&mut self) {
+        self.settings_notifications
+            .toggle(NOTIFICATION_SINKS[self.settings_sink_idx]);
+    }
+
+    pub fn cycle_ntfy_priority(&mut self, forward: bool) {
+        let len = notifications::NTFY_PRIORITIES.len();
+        let idx = self.settings_notifications.ntfy.priority_idx();
+        let idx = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+        self.settings_notifications.ntfy.priority = notifications::NTFY_PRIORITIES[idx].to_string();
+    }
+
+    pub fn cycle_smtp_security(&mut self, forward: bool) {
+        let len = notifications::SMTP_SECURITY.len();
+        let idx = self.settings_notifications.smtp.security_idx();
+        let idx = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+        self.settings_notifications.smtp.security = notifications::SMTP_SECURITY[idx].to_string();
+    }
+
+    pub fn cycle_quiet_mode(&mut self, forward: bool) {
+        let len = notifications::QUIET_MODES.len();
+        let idx = self.settings_notifications.quiet.mode_idx();
+        let idx = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+        self.settings_notifications.quiet.mode = notifications::QUIET_MODES[idx].to_string();
+    }
+
+    /// Toggle the sink under the cursor in the alert popup's routing row.
+    pub fn toggle_alert_sink(&mut self) {
+        let name = NOTIFICATION_SINKS[self.alert_sink_idx];
+        if self.alert_sinks.iter().any(|s| s == name) {
+            self.alert_sinks.retain(|s| s != name);
         } else {
-            self.settings_notification_idx = (self.settings_notification_idx + len - 1) % len;
+            self.alert_sinks.push(name.to_string());
         }
     }
 
+    /// Cycle the alert's ntfy priority, with "" meaning the configured one.
+    pub fn cycle_alert_ntfy_priority(&mut self, forward: bool) {
+        let len = notifications::NTFY_PRIORITIES.len() + 1;
+        let idx = notifications::NTFY_PRIORITIES
+            .iter()
+            .position(|p| *p == self.alert_ntfy.priority)
+            .map_or(0, |i| i + 1);
+        let idx = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+        self.alert_ntfy.priority = match idx {
+            0 => String::new(),
+            i => notifications::NTFY_PRIORITIES[i - 1].to_string(),
+        };
+    }
+
+    /// The text being typed on the alert popup's focused row, if it takes text.
+    pub fn alert_text_buf(&mut self) -> Option<&mut String> {
+        match self.alert_field {
+            AlertField::Target => Some(&mut self.alert_input_buf),
+            AlertField::NtfyTags => Some(&mut self.alert_ntfy.tags),
+            AlertField::NtfyClickUrl => Some(&mut self.alert_ntfy.click_url),
+            AlertField::Sinks | AlertField::NtfyPriority => None,
+        }
+    }
+
+    pub fn cycle_webhook_method(&mut self, forward: bool) {
+        let len = notifications::WEBHOOK_METHODS.len();
+        let idx = self.settings_notifications.webhook.method_idx();
+        let idx = if forward { (idx + 1) % len } else { (idx + len - 1) % len };
+        self.settings_notifications.webhook.method = notifications::WEBHOOK_METHODS[idx].to_string();
+    }
+
     pub fn buy_price_for(&self, coin_id: &str) -> Option<f64> {
         self.holdings
             .iter()
@@ -456,6 +1495,55 @@ impl App {
     }
 }
 
+/// Returns the observed value (price, volume multiple or rank) if the alert fires.
+fn evaluate_alert(alert: &PriceAlert, coin: &Coin, prior: &[MarketSample]) -> Option<f64> {
+    match alert.kind {
+        AlertKind::Price => {
+            let hit = match alert.direction {
+                AlertDirection::Above => coin.current_price >= alert.target_price,
+                AlertDirection::Below => coin.current_price <= alert.target_price,
+            };
+            hit.then_some(coin.current_price)
+        }
+        AlertKind::VolumeSpike => {
+            if prior.len() < MIN_VOLUME_SAMPLES {
+                return None;
+            }
+            let avg = prior.iter().map(|s| s.total_volume).sum::<f64>() / prior.len() as f64;
+            if avg <= 0.0 {
+                return None;
+            }
+            let multiple = coin.total_volume / avg;
+            (multiple >= alert.target_price).then_some(multiple)
+        }
+        AlertKind::Rank => {
+            // Unranked coins sit below every bracket
+            let bracket = alert.target_price as u32;
+            let prev = prior.last()?.market_cap_rank.unwrap_or(u32::MAX);
+            let now = coin.market_cap_rank.unwrap_or(u32::MAX);
+            let hit = match alert.direction {
+                AlertDirection::Above => prev > bracket && now <= bracket,
+                AlertDirection::Below => prev <= bracket && now > bracket,
+            };
+            hit.then_some(now as f64)
+        }
+    }
+}
+
+/// Append newer points to a cached series, keeping at least `step` between
+/// points. The last point may sit closer, since it carries the latest price.
+fn merge_price_tail(mut cached: Vec<PricePoint>, tail: Vec<PricePoint>, step: i64) -> Vec<PricePoint> {
+    for point in tail {
+        let len = cached.len();
+        match cached.last() {
+            Some(last) if point.ts <= last.ts => continue,
+            _ if len >= 2 && point.ts - cached[len - 2].ts < step => cached[len - 1] = point,
+            _ => cached.push(point),
+        }
+    }
+    cached
+}
+
 fn log_path() -> std::path::PathBuf {
     let mut path = dirs::config_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
     path.push("bags");
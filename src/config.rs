@@ -11,6 +11,30 @@ pub struct Config {
     pub currency: String,
     #[serde(default = "default_theme")]
     pub theme: String,
+    /// Coins listed on the Markets tab, by market cap
+    #[serde(default = "default_markets_limit")]
+    pub markets_limit: u32,
+    /// Start with one page and fetch more when scrolling past the end
+    #[serde(default)]
+    pub lazy_load: bool,
+    #[serde(default)]
+    pub digest: DigestSchedule,
+}
+
+/// When to send the portfolio digest notification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestSchedule {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Time of day in `timezone`, as "HH:MM"
+    #[serde(default = "default_digest_time")]
+    pub time: String,
+    /// Days to send on ("mon".."sun"); empty means every day
+    #[serde(default)]
+    pub weekdays: Vec<String>,
+    /// IANA timezone name such as "Europe/Berlin", or "local"
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
 }
 
 fn default_refresh() -> u64 {
@@ -25,12 +49,38 @@ fn default_theme() -> String {
     "dark".to_string()
 }
 
+fn default_markets_limit() -> u32 {
+    50
+}
+
+fn default_digest_time() -> String {
+    "08:00".to_string()
+}
+
+fn default_timezone() -> String {
+    "local".to_string()
+}
+
+impl Default for DigestSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            time: default_digest_time(),
+            weekdays: Vec::new(),
+            timezone: default_timezone(),
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             refresh_interval_secs: default_refresh(),
             currency: default_currency(),
             theme: default_theme(),
+            markets_limit: default_markets_limit(),
+            lazy_load: false,
+            digest: DigestSchedule::default(),
         }
     }
 }
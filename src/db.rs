@@ -1,8 +1,11 @@
 use anyhow::{Context, Result};
 use rusqlite::Connection;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
-use crate::types::{AlertDirection, Holding, PriceAlert};
+use crate::types::{
+    AlertDirection, AlertKind, AlertNtfy, Coin, Holding, MarketSample, OutboxEntry, PriceAlert, PricePoint,
+};
 
 pub struct Db {
     conn: Connection,
@@ -17,7 +20,17 @@ impl Db {
 
         let conn = Connection::open(&path)
             .context("Failed to open database")?;
+        Self::init(conn, password)
+    }
+
+    /// A throwaway database for tests.
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?, "test")
+    }
 
+    /// Unlock `conn` and bring its schema up to date.
+    fn init(conn: Connection, password: &str) -> Result<Self> {
         conn.pragma_update(None, "key", password)?;
 
         // Test that the key works by querying the schema
@@ -26,8 +39,12 @@ impl Db {
                 row.get::<_, i64>(0)
             });
 
-        if ok.is_err() {
-            anyhow::bail!("Wrong password or corrupted database");
+        // SQLCipher reports a wrong key as "file is not a database"
+        if let Err(e) = ok {
+            if e.sqlite_error_code() == Some(rusqlite::ErrorCode::NotADatabase) {
+                anyhow::bail!("Wrong password or corrupted database");
+            }
+            return Err(e).context("Failed to read database");
         }
 
         conn.execute_batch(
@@ -49,12 +66,70 @@ impl Db {
                 target_price REAL NOT NULL,
                 direction TEXT NOT NULL DEFAULT 'above',
                 triggered INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS market_history (
+                coin_id TEXT NOT NULL,
+                recorded_at INTEGER NOT NULL,
+                total_volume REAL NOT NULL,
+                market_cap_rank INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS market_history_coin
+                ON market_history (coin_id, recorded_at);
+            CREATE TABLE IF NOT EXISTS price_history (
+                coin_id TEXT NOT NULL,
+                currency TEXT NOT NULL,
+                days INTEGER NOT NULL,
+                ts INTEGER NOT NULL,
+                price REAL NOT NULL,
+                volume REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY (coin_id, currency, days, ts)
+            );
+            CREATE TABLE IF NOT EXISTS market_snapshot (
+                kind TEXT PRIMARY KEY,
+                payload TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS notification_outbox (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                sink TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at INTEGER NOT NULL,
+                last_error TEXT,
+                created_at INTEGER NOT NULL
             );",
         )?;
 
         // Migration: add buy_price column if it doesn't exist
         let _ = conn.execute_batch("ALTER TABLE holdings ADD COLUMN buy_price REAL");
 
+        // Migration: alert audit trail (unix timestamps + price at trigger time)
+        let _ = conn.execute_batch("ALTER TABLE price_alerts ADD COLUMN created_at INTEGER");
+        let _ = conn.execute_batch("ALTER TABLE price_alerts ADD COLUMN triggered_at INTEGER");
+        let _ = conn.execute_batch("ALTER TABLE price_alerts ADD COLUMN trigger_price REAL");
+
+        // Migration: non-price alert kinds
+        let _ = conn.execute_batch(
+            "ALTER TABLE price_alerts ADD COLUMN kind TEXT NOT NULL DEFAULT 'price'",
+        );
+
+        // Migration: per-alert notification routing (comma separated sink names)
+        let _ = conn.execute_batch(
+            "ALTER TABLE price_alerts ADD COLUMN sinks TEXT NOT NULL DEFAULT ''",
+        );
+
+        // Migration: per-alert ntfy headers (empty means the ntfy settings)
+        let _ = conn.execute_batch(
+            "ALTER TABLE price_alerts ADD COLUMN ntfy_priority TEXT NOT NULL DEFAULT ''",
+        );
+        let _ = conn.execute_batch(
+            "ALTER TABLE price_alerts ADD COLUMN ntfy_tags TEXT NOT NULL DEFAULT ''",
+        );
+        let _ = conn.execute_batch(
+            "ALTER TABLE price_alerts ADD COLUMN ntfy_click_url TEXT NOT NULL DEFAULT ''",
+        );
+
         Ok(Self { conn })
     }
 
@@ -170,30 +245,73 @@ impl Db {
 
     // -- Price Alerts --
 
-    pub fn add_alert(&self, coin_id: &str, target_price: f64, direction: &str) -> Result<()> {
+    pub fn add_alert(
+        &self,
+        coin_id: &str,
+        kind: AlertKind,
+        target_price: f64,
+        direction: AlertDirection,
+        sinks: &[String],
+        ntfy: &AlertNtfy,
+    ) -> Result<i64> {
+        // Volume spikes only fire upwards
+        let direction = match (kind, direction) {
+            (AlertKind::VolumeSpike, _) | (_, AlertDirection::Above) => "above",
+            (_, AlertDirection::Below) => "below",
+        };
         self.conn.execute(
-            "INSERT INTO price_alerts (coin_id, target_price, direction) VALUES (?1, ?2, ?3)",
-            rusqlite::params![coin_id, target_price, direction],
+            "INSERT INTO price_alerts (coin_id, kind, target_price, direction, created_at, sinks,
+                                       ntfy_priority, ntfy_tags, ntfy_click_url)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                coin_id,
+                kind.as_str(),
+                target_price,
+                direction,
+                chrono::Utc::now().timestamp(),
+                sinks.join(","),
+                ntfy.priority,
+                ntfy.tags,
+                ntfy.click_url
+            ],
         )?;
-        Ok(())
+        Ok(self.conn.last_insert_rowid())
     }
 
     pub fn get_alerts(&self) -> Result<Vec<PriceAlert>> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT coin_id, target_price, direction, triggered FROM price_alerts")?;
+        let mut stmt = self.conn.prepare(
+            "SELECT id, coin_id, target_price, direction, triggered, created_at, triggered_at,
+                    trigger_price, kind, sinks, ntfy_priority, ntfy_tags, ntfy_click_url
+             FROM price_alerts ORDER BY id",
+        )?;
         let rows = stmt.query_map([], |row| {
-            let dir_str: String = row.get(2)?;
+            let dir_str: String = row.get(3)?;
             let direction = if dir_str == "below" {
                 AlertDirection::Below
             } else {
                 AlertDirection::Above
             };
             Ok(PriceAlert {
-                coin_id: row.get(0)?,
-                target_price: row.get(1)?,
+                id: row.get(0)?,
+                coin_id: row.get(1)?,
+                kind: AlertKind::parse(&row.get::<_, String>(8)?),
+                target_price: row.get(2)?,
                 direction,
-                triggered: row.get::<_, i32>(3)? != 0,
+                triggered: row.get::<_, i32>(4)? != 0,
+                created_at: row.get(5)?,
+                triggered_at: row.get(6)?,
+                trigger_price: row.get(7)?,
+                sinks: row
+                    .get::<_, String>(9)?
+                    .split(',')
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect(),
+                ntfy: AlertNtfy {
+                    priority: row.get(10)?,
+                    tags: row.get(11)?,
+                    click_url: row.get(12)?,
+                },
             })
         })?;
         let mut out = Vec::new();
@@ -203,21 +321,244 @@ impl Db {
         Ok(out)
     }
 
-    pub fn mark_alert_triggered(&self, coin_id: &str, target_price: f64) -> Result<()> {
+    pub fn mark_alert_triggered(&self, id: i64, trigger_price: f64, triggered_at: i64) -> Result<()> {
         self.conn.execute(
-            "UPDATE price_alerts SET triggered = 1 WHERE coin_id = ?1 AND target_price = ?2",
-            rusqlite::params![coin_id, target_price],
+            "UPDATE price_alerts SET triggered = 1, triggered_at = ?2, trigger_price = ?3 WHERE id = ?1",
+            rusqlite::params![id, triggered_at, trigger_price],
         )?;
         Ok(())
     }
 
-    #[allow(dead_code)]
-    pub fn delete_alert(&self, coin_id: &str, target_price: f64) -> Result<()> {
+    pub fn delete_alert(&self, id: i64) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM price_alerts WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    // -- Market history --
+
+    pub fn add_market_samples(&self, coins: &[Coin], recorded_at: i64) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO market_history (coin_id, recorded_at, total_volume, market_cap_rank)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for coin in coins {
+                stmt.execute(rusqlite::params![
+                    coin.id,
+                    recorded_at,
+                    coin.total_volume,
+                    coin.market_cap_rank
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn prune_market_history(&self, older_than: i64) -> Result<()> {
         self.conn.execute(
-            "DELETE FROM price_alerts WHERE coin_id = ?1 AND target_price = ?2",
-            rusqlite::params![coin_id, target_price],
+            "DELETE FROM market_history WHERE recorded_at < ?1",
+            [older_than],
         )?;
         Ok(())
     }
 
+    pub fn get_market_history(&self, since: i64) -> Result<HashMap<String, Vec<MarketSample>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT coin_id, recorded_at, total_volume, market_cap_rank FROM market_history
+             WHERE recorded_at >= ?1 ORDER BY recorded_at",
+        )?;
+        let rows = stmt.query_map([since], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                MarketSample {
+                    recorded_at: row.get(1)?,
+                    total_volume: row.get(2)?,
+                    market_cap_rank: row.get(3)?,
+                },
+            ))
+        })?;
+        let mut out: HashMap<String, Vec<MarketSample>> = HashMap::new();
+        for r in rows {
+            let (coin_id, sample) = r?;
+            out.entry(coin_id).or_default().push(sample);
+        }
+        Ok(out)
+    }
+
+    // -- Price history cache --
+
+    /// Cached points for one chart range, oldest first.
+    pub fn get_price_history(&self, coin_id: &str, currency: &str, days: u32) -> Result<Vec<PricePoint>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ts, price, volume FROM price_history
+             WHERE coin_id = ?1 AND currency = ?2 AND days = ?3 ORDER BY ts",
+        )?;
+        let rows = stmt.query_map(rusqlite::params![coin_id, currency, days], |row| {
+            Ok(PricePoint {
+                ts: row.get(0)?,
+                price: row.get(1)?,
+                volume: row.get(2)?,
+            })
+        })?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Replace the cached points for one chart range.
+    pub fn set_price_history(&self, coin_id: &str, currency: &str, days: u32, points: &[PricePoint]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM price_history WHERE coin_id = ?1 AND currency = ?2 AND days = ?3",
+            rusqlite::params![coin_id, currency, days],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO price_history (coin_id, currency, days, ts, price, volume)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for p in points {
+                stmt.execute(rusqlite::params![coin_id, currency, days, p.ts, p.price, p.volume])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    // -- Offline snapshot --
+
+    /// Store the last successful API response of one kind (e.g. "markets:usd").
+    pub fn set_snapshot(&self, kind: &str, payload: &str, fetched_at: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO market_snapshot (kind, payload, fetched_at) VALUES (?1, ?2, ?3)",
+            rusqlite::params![kind, payload, fetched_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_snapshot(&self, kind: &str) -> Option<(String, i64)> {
+        self.conn
+            .query_row(
+                "SELECT payload, fetched_at FROM market_snapshot WHERE kind = ?1",
+                [kind],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok()
+    }
+
+    // -- Notification outbox --
+
+    pub fn enqueue_notification(&self, sink: &str, payload: &str, now: i64, not_before: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO notification_outbox (sink, payload, next_attempt_at, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![sink, payload, not_before, now],
+        )?;
+        Ok(())
+    }
+
+    /// Fetch pending entries that are due and push their next attempt out by
+    /// `lease_secs`, so a concurrent flush won't pick them up twice.
+    pub fn claim_due_notifications(&self, now: i64, lease_secs: i64) -> Result<Vec<OutboxEntry>> {
+        let tx = self.conn.unchecked_transaction()?;
+        let out = {
+            let mut stmt = tx.prepare(
+                "SELECT id, sink, payload, attempts FROM notification_outbox
+                 WHERE status = 'pending' AND next_attempt_at <= ?1 ORDER BY id",
+            )?;
+            let rows = stmt.query_map([now], |row| {
+                Ok(OutboxEntry {
+                    id: row.get(0)?,
+                    sink: row.get(1)?,
+                    payload: row.get(2)?,
+                    attempts: row.get(3)?,
+                })
+            })?;
+            let mut out = Vec::new();
+            for r in rows {
+                out.push(r?);
+            }
+            out
+        };
+        for entry in &out {
+            tx.execute(
+                "UPDATE notification_outbox SET next_attempt_at = ?2 WHERE id = ?1",
+                rusqlite::params![entry.id, now + lease_secs],
+            )?;
+        }
+        tx.commit()?;
+        Ok(out)
+    }
+
+    pub fn complete_notification(&self, id: i64) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM notification_outbox WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    pub fn reschedule_notification(&self, id: i64, attempts: u32, next_attempt_at: i64, error: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE notification_outbox SET attempts = ?2, next_attempt_at = ?3, last_error = ?4
+             WHERE id = ?1",
+            rusqlite::params![id, attempts, next_attempt_at, error],
+        )?;
+        Ok(())
+    }
+
+    pub fn fail_notification(&self, id: i64, attempts: u32, error: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE notification_outbox SET status = 'failed', attempts = ?2, last_error = ?3
+             WHERE id = ?1",
+            rusqlite::params![id, attempts, error],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claimed_notifications_come_back_when_the_lease_expires() {
+        let db = Db::open_in_memory().unwrap();
+        db.enqueue_notification("ntfy", "{}", 0, 100).unwrap();
+        assert!(db.claim_due_notifications(99, 120).unwrap().is_empty());
+
+        let claimed = db.claim_due_notifications(100, 120).unwrap();
+        assert_eq!(claimed.len(), 1);
+        // Hidden from other flushes while the lease runs
+        assert!(db.claim_due_notifications(219, 120).unwrap().is_empty());
+
+        let again = db.claim_due_notifications(220, 120).unwrap();
+        assert_eq!(again.len(), 1);
+        assert_eq!(again[0].id, claimed[0].id);
+    }
+
+    #[test]
+    fn volume_alerts_are_stored_upward() {
+        let db = Db::open_in_memory().unwrap();
+        let ntfy = AlertNtfy::default();
+        db.add_alert("bitcoin", AlertKind::VolumeSpike, 3.0, AlertDirection::Below, &[], &ntfy).unwrap();
+        db.add_alert("bitcoin", AlertKind::Rank, 10.0, AlertDirection::Below, &[], &ntfy).unwrap();
+        let alerts = db.get_alerts().unwrap();
+        assert_eq!(alerts[0].direction, AlertDirection::Above);
+        assert_eq!(alerts[1].direction, AlertDirection::Below);
+    }
+
+    #[test]
+    fn finished_notifications_are_not_claimed() {
+        let db = Db::open_in_memory().unwrap();
+        db.enqueue_notification("ntfy", "{}", 0, 0).unwrap();
+        db.enqueue_notification("slack", "{}", 0, 0).unwrap();
+        let claimed = db.claim_due_notifications(0, 0).unwrap();
+        db.complete_notification(claimed[0].id).unwrap();
+        db.fail_notification(claimed[1].id, 8, "gave up").unwrap();
+        assert!(db.claim_due_notifications(1000, 0).unwrap().is_empty());
+    }
 }
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc};
+
+use tokio::sync::{mpsc, Mutex};
+
+use crate::api::CoinGeckoClient;
+use crate::app::log_error;
+use crate::config::{Config, DigestSchedule};
+use crate::db::Db;
+use crate::notifications::{self, AlertMessage, DeliveryEvent, NotificationSettings};
+use crate::types::*;
+use crate::ui::format_price;
+
+const LAST_SENT_KEY: &str = "digest_last_sent_at";
+const LAST_VALUE_KEY: &str = "digest_last_value";
+const LAST_PRICES_KEY: &str = "digest_last_prices";
+/// Gainers/losers listed in each direction.
+const TOP_MOVERS: usize = 3;
+
+pub enum DigestState {
+    /// Never sent; record a baseline so the first digest has something to compare against
+    Baseline,
+    Due,
+    NotDue,
+}
+
+/// Whether a digest should go out now, or `None` if it is disabled or the
+/// schedule cannot be parsed.
+pub fn state(db: &Db, schedule: &DigestSchedule, now: DateTime<Utc>) -> Option<DigestState> {
+    if !schedule.enabled {
+        return None;
+    }
+    let slot = last_slot(schedule, now)?;
+    let state = match last_sent_at(db) {
+        None => DigestState::Baseline,
+        Some(sent) if sent < slot.timestamp() => DigestState::Due,
+        Some(_) => DigestState::NotDue,
+    };
+    Some(state)
+}
+
+/// The most recent scheduled time at or before `now`.
+pub fn last_slot(schedule: &DigestSchedule, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let time = NaiveTime::parse_from_str(schedule.time.trim(), "%H:%M").ok()?;
+    let weekdays: Vec<chrono::Weekday> = schedule
+        .weekdays
+        .iter()
+        .filter_map(|d| d.trim().parse().ok())
+        .collect();
+    if schedule.timezone.trim().eq_ignore_ascii_case("local") {
+        slot_in(&chrono::Local, time, &weekdays, now)
+    } else {
+        let tz: chrono_tz::Tz = schedule.timezone.trim().parse().ok()?;
+        slot_in(&tz, time, &weekdays, now)
+    }
+}
+
+fn slot_in<Tz: TimeZone>(
+    tz: &Tz,
+    time: NaiveTime,
+    weekdays: &[chrono::Weekday],
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let today = now.with_timezone(tz).date_naive();
+    (0..=7).find_map(|back| {
+        let date = today - Duration::days(back);
+        if !weekdays.is_empty() && !weekdays.contains(&date.weekday()) {
+            return None;
+        }
+        // Skip times that fall into a DST gap
+        let slot = tz.from_local_datetime(&date.and_time(time)).earliest()?.with_timezone(&Utc);
+        (slot <= now).then_some(slot)
+    })
+}
+
+fn last_sent_at(db: &Db) -> Option<i64> {
+    db.get_setting(LAST_SENT_KEY)?.parse().ok()
+}
+
+/// Store the portfolio as of `now` as the reference for the next digest.
+pub fn record(db: &Db, holdings: &[Holding], coins: &[Coin], now: DateTime<Utc>) -> anyhow::Result<()> {
+    let prices: HashMap<&str, f64> = holdings
+        .iter()
+        .filter_map(|h| coins.iter().find(|c| c.id == h.coin_id))
+        .map(|c| (c.id.as_str(), c.current_price))
+        .collect();
+    db.set_setting(LAST_SENT_KEY, &now.timestamp().to_string())?;
+    db.set_setting(LAST_VALUE_KEY, &portfolio_value(holdings, coins).to_string())?;
+    db.set_setting(LAST_PRICES_KEY, &serde_json::to_string(&prices)?)?;
+    Ok(())
+}
+
+fn portfolio_value(holdings: &[Holding], coins: &[Coin]) -> f64 {
+    holdings
+        .iter()
+        .filter_map(|h| {
+            coins
+                .iter()
+                .find(|c| c.id == h.coin_id)
+                .map(|c| c.current_price * h.amount)
+        })
+        .sum()
+}
+
+/// Summarise the portfolio since the previous digest.
+pub fn build(
+    db: &Db,
+    holdings: &[Holding],
+    coins: &[Coin],
+    alerts: &[PriceAlert],
+    currency: &str,
+) -> AlertMessage {
+    let sym = currency_symbol(currency);
+    let since = last_sent_at(db);
+    let last_value: Option<f64> = db.get_setting(LAST_VALUE_KEY).and_then(|v| v.parse().ok());
+    let last_prices: HashMap<String, f64> = db
+        .get_setting(LAST_PRICES_KEY)
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default();
+
+    let total = portfolio_value(holdings, coins);
+    let mut lines = vec![format!("Portfolio: {}{}", sym, format_price(total))];
+    match last_value {
+        Some(prev) if prev > 0.0 => {
+            let diff = total - prev;
+            let sign = if diff >= 0.0 { "+" } else { "-" };
+            lines.push(format!(
+                "Change: {}{}{} ({:+.2}%)",
+                sign,
+                sym,
+                format_price(diff.abs()),
+                diff / prev * 100.0
+            ));
+        }
+        _ => lines.push("Change: n/a".to_string()),
+    }
+
+    // % move per holding since the last digest, falling back to 24h
+    let mut moves: Vec<(&Coin, f64)> = holdings
+        .iter()
+        .filter(|h| h.amount > 0.0)
+        .filter_map(|h| coins.iter().find(|c| c.id == h.coin_id))
+        .filter_map(|c| {
+            let pct = match last_prices.get(&c.id) {
+                Some(&prev) if prev > 0.0 => Some((c.current_price - prev) / prev * 100.0),
+                _ => c.price_change_percentage_24h_in_currency,
+            };
+            pct.map(|p| (c, p))
+        })
+        .collect();
+    moves.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let gainers: Vec<String> = moves
+        .iter()
+        .filter(|(_, p)| *p > 0.0)
+        .take(TOP_MOVERS)
+        .map(|(c, p)| format!("  {} {:+.2}%", c.symbol.to_uppercase(), p))
+        .collect();
+    let losers: Vec<String> = moves
+        .iter()
+        .rev()
+        .filter(|(_, p)| *p < 0.0)
+        .take(TOP_MOVERS)
+        .map(|(c, p)| format!("  {} {:+.2}%", c.symbol.to_uppercase(), p))
+        .collect();
+    if !gainers.is_empty() {
+        lines.push("Top gainers:".to_string());
+        lines.extend(gainers);
+    }
+    if !losers.is_empty() {
+        lines.push("Top losers:".to_string());
+        lines.extend(losers);
+    }
+
+    let fired: Vec<String> = alerts
+        .iter()
+        .filter(|a| match (a.triggered_at, since) {
+            (Some(at), Some(since)) => at >= since,
+            (Some(_), None) => true,
+            _ => false,
+        })
+        .map(|a| {
+            let name = coins
+                .iter()
+                .find(|c| c.id == a.coin_id)
+                .map(|c| c.symbol.to_uppercase())
+                .unwrap_or_else(|| a.coin_id.clone());
+            let what = match a.kind {
+                AlertKind::Price => format!(
+                    "{} {}{}",
+                    notifications::direction_str(a.direction),
+                    sym,
+                    format_price(a.target_price)
+                ),
+                AlertKind::VolumeSpike => format!("volume above {}x", a.target_price),
+                AlertKind::Rank => {
+                    format!("{} the top {}", notifications::rank_verb(a.direction), a.target_price as u32)
+                }
+            };
+            format!("  {} {}", name, what)
+        })
+        .collect();
+    if fired.is_empty() {
+        lines.push("No alerts triggered".to_string());
+    } else {
+        lines.push(format!("Alerts triggered ({}):", fired.len()));
+        lines.extend(fired);
+    }
+
+    AlertMessage::digest("bags portfolio digest".to_string(), lines.join("\n"))
+}
+
+/// Headless digest loop for `bags digest`: checks the schedule every minute,
+/// or sends once and exits when `now` is set.
+pub async fn run_headless(config: &Config, db: Db, now: bool) -> anyhow::Result<()> {
+    if !now && !config.digest.enabled {
+        anyhow::bail!("digest is disabled in config.yaml (set digest.enabled: true, or pass --now)");
+    }
+    let client = CoinGeckoClient::new(&config.currency, &db.get_setting("coingecko_api_key").unwrap_or_default());
+    let settings = NotificationSettings::load(&db);
+    let sinks = settings.build_all_sinks()?;
+    let routed = settings.route(&sinks, &[]);
+    if routed.is_empty() {
+        anyhow::bail!("no notification sinks are enabled and configured");
+    }
+    let db = Arc::new(Mutex::new(db));
+    let (events, mut results) = mpsc::unbounded_channel();
+
+    loop {
+        let ts = Utc::now();
+        let state = if now {
+            Some(DigestState::Due)
+        } else {
+            state(&*db.lock().await, &config.digest, ts)
+        };
+        if let Some(state @ (DigestState::Due | DigestState::Baseline)) = state {
+            // A failed fetch leaves the digest due, so the next tick retries it
+            match prepare(&db, &client, &config.currency, state, ts).await {
+                Ok(Some(msg)) => {
+                    notifications::queue_and_deliver(&db, &routed, &msg, &settings.quiet, &events).await;
+                }
+                Ok(None) => {}
+                Err(e) if now => return Err(e),
+                Err(e) => {
+                    log_error(&format!("Digest: {:#}", e));
+                    eprintln!("Digest failed, retrying in a minute: {:#}", e);
+                }
+            }
+        } else {
+            notifications::flush_outbox(db.clone(), &sinks, events.clone());
+        }
+        while let Ok(event) = results.try_recv() {
+            if let DeliveryEvent::Failed { sink, error } = event {
+                eprintln!("Digest via {} failed: {}", sink, error);
+            }
+        }
+        if now {
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    }
+}
+
+/// Price the holdings, build the digest if one is due and record the new
+/// baseline.
+async fn prepare(
+    db: &Arc<Mutex<Db>>,
+    client: &CoinGeckoClient,
+    currency: &str,
+    state: DigestState,
+    ts: DateTime<Utc>,
+) -> anyhow::Result<Option<AlertMessage>> {
+    let (holdings, alerts) = {
+        let db = db.lock().await;
+        (db.get_holdings()?, db.get_alerts()?)
+    };
+    let coins = fetch_holding_coins(client, &holdings).await?;
+    let db = db.lock().await;
+    let msg = matches!(state, DigestState::Due).then(|| build(&db, &holdings, &coins, &alerts, currency));
+    record(&db, &holdings, &coins, ts)?;
+    Ok(msg)
+}
+
+/// Market data for every held coin, in one request.
+async fn fetch_holding_coins(client: &CoinGeckoClient, holdings: &[Holding]) -> anyhow::Result<Vec<Coin>> {
+    let ids: Vec<String> = holdings.iter().map(|h| h.coin_id.clone()).collect();
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    client.fetch_coins_market(&ids).await
+}
@@ -0,0 +1,232 @@
+//! Technical indicators computed from a price series.
+
+/// Simple moving average over `period` samples, aligned with `prices`;
+/// `None` until a full window is available.
+pub fn sma(prices: &[f64], period: usize) -> Vec<Option<f64>> {
+    let mut out = vec![None; prices.len()];
+    if period == 0 || prices.len() < period {
+        return out;
+    }
+    let mut sum: f64 = prices[..period].iter().sum();
+    out[period - 1] = Some(sum / period as f64);
+    for i in period..prices.len() {
+        sum += prices[i] - prices[i - period];
+        out[i] = Some(sum / period as f64);
+    }
+    out
+}
+
+/// Exponential moving average over `period` samples, seeded with the SMA of
+/// the first window.
+pub fn ema(prices: &[f64], period: usize) -> Vec<Option<f64>> {
+    let mut out = vec![None; prices.len()];
+    if period == 0 || prices.len() < period {
+        return out;
+    }
+    let k = 2.0 / (period as f64 + 1.0);
+    let mut prev = prices[..period].iter().sum::<f64>() / period as f64;
+    out[period - 1] = Some(prev);
+    for i in period..prices.len() {
+        prev = prices[i] * k + prev * (1.0 - k);
+        out[i] = Some(prev);
+    }
+    out
+}
+
+/// Relative strength index with Wilder's smoothing, 0-100.
+pub fn rsi(prices: &[f64], period: usize) -> Vec<Option<f64>> {
+    let mut out = vec![None; prices.len()];
+    if period == 0 || prices.len() <= period {
+        return out;
+    }
+    let value = |gain: f64, loss: f64| {
+        if loss == 0.0 {
+            100.0
+        } else {
+            100.0 - 100.0 / (1.0 + gain / loss)
+        }
+    };
+    let (mut gain, mut loss) = (0.0, 0.0);
+    for i in 1..=period {
+        let change = prices[i] - prices[i - 1];
+        gain += change.max(0.0);
+        loss += (-change).max(0.0);
+    }
+    gain /= period as f64;
+    loss /= period as f64;
+    out[period] = Some(value(gain, loss));
+    for i in period + 1..prices.len() {
+        let change = prices[i] - prices[i - 1];
+        gain = (gain * (period - 1) as f64 + change.max(0.0)) / period as f64;
+        loss = (loss * (period - 1) as f64 + (-change).max(0.0)) / period as f64;
+        out[i] = Some(value(gain, loss));
+    }
+    out
+}
+
+pub struct Macd {
+    /// Fast EMA minus slow EMA
+    pub line: Vec<Option<f64>>,
+    /// EMA of `line`
+    pub signal: Vec<Option<f64>>,
+    pub histogram: Vec<Option<f64>>,
+}
+
+pub fn macd(prices: &[f64], fast: usize, slow: usize, signal: usize) -> Macd {
+    let (fast, slow) = (ema(prices, fast), ema(prices, slow));
+    let line: Vec<Option<f64>> = fast
+        .iter()
+        .zip(&slow)
+        .map(|(f, s)| Some(f.as_ref()? - s.as_ref()?))
+        .collect();
+    // The signal EMA starts where the MACD line does
+    let start = line.iter().position(Option::is_some).unwrap_or(line.len());
+    let defined: Vec<f64> = line[start..].iter().flatten().copied().collect();
+    let mut signal_line = vec![None; start];
+    signal_line.extend(ema(&defined, signal));
+    let histogram = line
+        .iter()
+        .zip(&signal_line)
+        .map(|(l, s)| Some(l.as_ref()? - s.as_ref()?))
+        .collect();
+    Macd { line, signal: signal_line, histogram }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Band {
+    pub lower: f64,
+    pub middle: f64,
+    pub upper: f64,
+}
+
+/// Bollinger Bands: SMA of `period` samples +/- `k` population standard deviations.
+pub fn bollinger(prices: &[f64], period: usize, k: f64) -> Vec<Option<Band>> {
+    let mut out = vec![None; prices.len()];
+    if period == 0 || prices.len() < period {
+        return out;
+    }
+    for i in period - 1..prices.len() {
+        let window = &prices[i + 1 - period..=i];
+        let mean = window.iter().sum::<f64>() / period as f64;
+        let var = window.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / period as f64;
+        let dev = k * var.sqrt();
+        out[i] = Some(Band { lower: mean - dev, middle: mean, upper: mean + dev });
+    }
+    out
+}
+
+/// Annualised realised volatility: sample standard deviation of log returns
+/// scaled by the square root of `samples_per_year`.
+pub fn volatility(prices: &[f64], samples_per_year: f64) -> Option<f64> {
+    let returns: Vec<f64> = prices
+        .windows(2)
+        .filter(|w| w[0] > 0.0 && w[1] > 0.0)
+        .map(|w| (w[1] / w[0]).ln())
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let var = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    Some(var.sqrt() * samples_per_year.sqrt())
+}
+
+/// Largest peak-to-trough fall as a fraction of the peak, e.g. 0.25 for -25%.
+pub fn max_drawdown(prices: &[f64]) -> f64 {
+    let mut peak = f64::NEG_INFINITY;
+    let mut worst: f64 = 0.0;
+    for &p in prices {
+        peak = peak.max(p);
+        if peak > 0.0 {
+            worst = worst.max((peak - p) / peak);
+        }
+    }
+    worst
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64, tol: f64) -> bool {
+        (a - b).abs() < tol
+    }
+
+    #[test]
+    fn sma_matches_window_means() {
+        let out = sma(&[1.0, 2.0, 3.0, 4.0, 5.0], 3);
+        assert_eq!(out, vec![None, None, Some(2.0), Some(3.0), Some(4.0)]);
+    }
+
+    #[test]
+    fn ema_tracks_linear_series_with_fixed_lag() {
+        // For a straight line the EMA lags by (period - 1) / 2 samples
+        let prices: Vec<f64> = (0..50).map(|i| i as f64).collect();
+        let out = ema(&prices, 10);
+        assert_eq!(out[8], None);
+        for (i, v) in out.iter().enumerate().skip(9) {
+            assert!(close(v.unwrap(), i as f64 - 4.5, 1e-9));
+        }
+    }
+
+    #[test]
+    fn rsi_matches_wilder_reference() {
+        // StockCharts' RSI(14) worked example, without intermediate rounding
+        let prices = [
+            44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61,
+            46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64,
+        ];
+        let out = rsi(&prices, 14);
+        assert!(out[..14].iter().all(Option::is_none));
+        let expected = [70.46, 66.25, 66.48, 69.35, 66.29, 57.92];
+        for (v, e) in out[14..].iter().zip(expected) {
+            assert!(close(v.unwrap(), e, 0.01), "{:?} vs {}", v, e);
+        }
+    }
+
+    #[test]
+    fn rsi_is_100_without_losses() {
+        let prices: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        assert_eq!(rsi(&prices, 14)[19], Some(100.0));
+    }
+
+    #[test]
+    fn macd_of_linear_series_is_constant() {
+        // EMA12 lags by 5.5 and EMA26 by 12.5, so the line settles at 7
+        let prices: Vec<f64> = (0..60).map(|i| 100.0 + i as f64).collect();
+        let m = macd(&prices, 12, 26, 9);
+        assert_eq!(m.line[24], None);
+        assert!(close(m.line[25].unwrap(), 7.0, 1e-9));
+        assert_eq!(m.signal[32], None);
+        assert!(close(m.signal[33].unwrap(), 7.0, 1e-9));
+        assert!(close(m.histogram[59].unwrap(), 0.0, 1e-9));
+    }
+
+    #[test]
+    fn bollinger_uses_population_deviation() {
+        // Mean 5, population standard deviation 2
+        let prices = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let out = bollinger(&prices, 8, 2.0);
+        assert_eq!(out[7], Some(Band { lower: 1.0, middle: 5.0, upper: 9.0 }));
+        assert_eq!(out[6], None);
+    }
+
+    #[test]
+    fn volatility_annualises_log_returns() {
+        // Log returns of +/-1%: sample deviation sqrt(4e-4 / 3)
+        let mut prices = vec![100.0];
+        for r in [0.01, -0.01, 0.01, -0.01] {
+            let last = *prices.last().unwrap();
+            prices.push(last * f64::exp(r));
+        }
+        let vol = volatility(&prices, 365.0).unwrap();
+        assert!(close(vol, (4e-4_f64 / 3.0).sqrt() * 365_f64.sqrt(), 1e-12));
+        assert_eq!(volatility(&[100.0, 101.0], 365.0), None);
+    }
+
+    #[test]
+    fn max_drawdown_finds_deepest_fall() {
+        assert!(close(max_drawdown(&[100.0, 120.0, 90.0, 130.0, 65.0, 80.0]), 0.5, 1e-12));
+        assert_eq!(max_drawdown(&[1.0, 2.0, 3.0]), 0.0);
+    }
+}
@@ -2,6 +2,8 @@ mod api;
 mod app;
 mod config;
 mod db;
+mod digest;
+mod indicators;
 mod notifications;
 mod theme;
 mod types;
@@ -11,6 +13,7 @@ use std::io;
 use std::time::Duration;
 
 use anyhow::Result;
+use clap::{Parser, Subcommand};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseEventKind, MouseButton},
     execute,
@@ -25,8 +28,27 @@ use config::Config;
 use db::Db;
 use types::*;
 
+#[derive(Parser)]
+#[command(name = "bags", version, about = "Crypto tracker for the terminal")]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Send the portfolio digest on its schedule without the TUI.
+    /// The database password is read from BAGS_PASSWORD or stdin.
+    Digest {
+        /// Send one digest immediately and exit
+        #[arg(long)]
+        now: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
     let config = Config::load()?;
 
     let db_path = dirs::data_local_dir()
@@ -36,6 +58,23 @@ async fn main() -> Result<()> {
 
     let is_new = !db_path.exists();
 
+    if let Some(Command::Digest { now }) = cli.command {
+        if is_new {
+            anyhow::bail!("no database at {}; run bags once to create it", db_path.display());
+        }
+        let password = match std::env::var("BAGS_PASSWORD") {
+            Ok(p) => p,
+            Err(_) => {
+                eprint!("Password: ");
+                let mut line = String::new();
+                io::stdin().read_line(&mut line)?;
+                line.trim_end_matches(['\r', '\n']).to_string()
+            }
+        };
+        let db = Db::open(&password)?;
+        return digest::run_headless(&config, db, now).await;
+    }
+
     let mut app = App::new(config.clone(), is_new);
 
     // Setup terminal
@@ -167,9 +206,12 @@ async fn run_app(
     // Now unlocked -- create client and fetch data
     let client = CoinGeckoClient::new(&app.config.currency, &app.coingecko_api_key);
     app.refresh_db_state().await;
+    app.load_market_history().await;
+    app.load_snapshot().await;
+    terminal.draw(|f| ui::draw(f, &mut *app))?;
     app.refresh_market_data(&client).await;
     app.refresh_alerts().await;
-    app.check_alerts();
+    app.check_alerts().await;
 
     // Fetch global stats in background (non-blocking)
     app.refresh_global_stats(&client).await;
@@ -177,6 +219,8 @@ async fn run_app(
     run_main_loop(terminal, app, client).await
 }
 
+// Key handlers read more naturally as `match key { .. => if .. }` than as guards
+#[allow(clippy::collapsible_match)]
 async fn run_main_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
@@ -191,6 +235,9 @@ async fn run_main_loop(
     loop {
         let refresh_dur = Duration::from_secs(app.config.refresh_interval_secs);
         app.update_refresh_display();
+        app.poll_deliveries();
+        app.flush_outbox_if_due();
+        app.check_digest().await;
 
         terminal.draw(|f| {
             let area = f.area();
@@ -199,13 +246,28 @@ async fn run_main_loop(
             ui::draw(f, &mut *app);
         })?;
 
+        // Cached chart is on screen by now; fetch whatever is missing
+        if app.popup_open || app.compare_open {
+            app.sync_chart(&client).await;
+        }
+        if app.popup_open {
+            app.sync_details(&client).await;
+            app.sync_candles(&client).await;
+        }
+        if app.input_mode == InputMode::Sectors {
+            app.sync_categories(&client).await;
+        }
+        if app.tab == Tab::Discover {
+            app.sync_trending(&client).await;
+        }
+
         // Auto-refresh
         if let Some(last) = app.last_refresh {
             if last.elapsed() >= refresh_dur {
                 app.refresh_market_data(&client).await;
                 app.refresh_db_state().await;
                 app.clamp_selection();
-                app.check_alerts();
+                app.check_alerts().await;
                 app.refresh_global_stats(&client).await;
             }
         }
@@ -216,55 +278,85 @@ async fn run_main_loop(
             // Handle mouse events
             if let Event::Mouse(mouse) = ev {
                 match mouse.kind {
-                    MouseEventKind::Down(MouseButton::Left) => {
-                        if app.input_mode == InputMode::Normal && !app.popup_open && !app.sort_picking {
-                            let row = mouse.row;
-                            let col = mouse.column;
-
-                            // Click on top bar tabs
-                            if row < top_bar_height {
-                                // Rough tab click detection based on position
-                                let tabs_start = 10_u16; // after "[bags] | "
-                                if col >= tabs_start {
-                                    let rel = col - tabs_start;
-                                    if rel < 7 { // "Markets"
-                                        app.tab = Tab::Markets;
-                                        app.selected = 0;
-                                        app.clamp_selection();
-                                    } else if rel < 22 { // " . Favourites"
-                                        app.tab = Tab::Favourites;
-                                        app.selected = 0;
-                                        app.clamp_selection();
-                                    } else if rel < 36 { // " . Portfolio"
-                                        app.tab = Tab::Portfolio;
-                                        app.selected = 0;
-                                        app.clamp_selection();
-                                    }
+                    MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left)
+                        if app.popup_open
+                            && app.chart_area.is_some_and(|a| {
+                                a.contains(ratatui::layout::Position::new(mouse.column, mouse.row))
+                            }) =>
+                    {
+                        app.set_chart_cursor_at(mouse.column);
+                    }
+                    MouseEventKind::Down(MouseButton::Left)
+                        if app.input_mode == InputMode::Normal
+                            && !app.popup_open
+                            && !app.compare_open
+                            && !app.sort_picking =>
+                    {
+                        let row = mouse.row;
+                        let col = mouse.column;
+
+                        // Click on top bar tabs
+                        if row < top_bar_height {
+                            // Rough tab click detection based on position
+                            let tabs_start = 10_u16; // after "[bags] | "
+                            if col >= tabs_start {
+                                let rel = col - tabs_start;
+                                if rel < 7 { // "Markets"
+                                    app.tab = Tab::Markets;
+                                    app.selected = 0;
+                                    app.clamp_selection();
+                                } else if rel < 22 { // " . Favourites"
+                                    app.tab = Tab::Favourites;
+                                    app.selected = 0;
+                                    app.clamp_selection();
+                                } else if rel < 36 { // " . Portfolio"
+                                    app.tab = Tab::Portfolio;
+                                    app.selected = 0;
+                                    app.clamp_selection();
+                                } else if rel < 47 { // " . Discover"
+                                    app.tab = Tab::Discover;
+                                    app.selected = 0;
+                                    app.clamp_selection();
                                 }
                             }
-                            // Click on table rows (below header, above bottom bar)
-                            else if row >= top_bar_height + 1 && row < bottom_bar_y {
-                                let table_row = (row - top_bar_height - 1) as usize;
-                                let target = app.scroll_offset + table_row;
-                                let visible_len = app.visible_coins().len();
-                                if target < visible_len {
-                                    app.selected = target;
-                                    app.adjust_scroll();
+                        }
+                        // Click a treemap block; a second click opens it
+                        else if app.treemap {
+                            let pos = ratatui::layout::Position::new(col, row);
+                            if let Some(i) = app.treemap_areas.iter().position(|a| a.contains(pos)) {
+                                if i == app.selected {
+                                    app.popup_open = true;
+                                    app.chart_view = ChartView::Day1;
+                                    app.chart_cursor = None;
+                                    app.load_cached_chart().await;
+                                } else {
+                                    app.selected = i;
                                 }
                             }
                         }
+                        // Click on table rows (below header, above bottom bar)
+                        else if row > top_bar_height && row < bottom_bar_y && app.tab != Tab::Discover {
+                            let table_row = (row - top_bar_height - 1) as usize;
+                            let target = app.scroll_offset + table_row;
+                            let visible_len = app.visible_coins().len();
+                            if target < visible_len {
+                                app.selected = target;
+                                app.adjust_scroll();
+                            }
+                        }
                     }
                     MouseEventKind::ScrollDown => {
-                        if app.input_mode == InputMode::Normal && !app.popup_open {
+                        if app.input_mode == InputMode::Normal && !app.popup_open && !app.compare_open {
                             let len = app.visible_coins().len();
                             if len > 0 {
                                 app.selected = (app.selected + 3).min(len - 1);
                             }
                             app.adjust_scroll();
+                            load_more_at_end(app, &client).await;
                         }
                     }
                     MouseEventKind::ScrollUp => {
-                        if app.input_mode == InputMode::Normal && !app.popup_open {
+                        if app.input_mode == InputMode::Normal && !app.popup_open && !app.compare_open {
                             app.selected = app.selected.saturating_sub(3);
                             app.adjust_scroll();
                         }
@@ -308,23 +400,54 @@ async fn run_main_loop(
                             app.input_mode = InputMode::Normal;
                             app.alert_input_buf.clear();
                         }
-                        KeyCode::Tab => {
+                        // Volume spikes only fire upwards
+                        KeyCode::Tab if app.alert_kind != AlertKind::VolumeSpike => {
                             app.alert_direction = match app.alert_direction {
                                 AlertDirection::Above => AlertDirection::Below,
                                 AlertDirection::Below => AlertDirection::Above,
                             };
                         }
+                        KeyCode::Down => {
+                            app.alert_field = app.alert_field.next();
+                        }
+                        KeyCode::Up => {
+                            app.alert_field = app.alert_field.prev();
+                        }
+                        KeyCode::Right => match app.alert_field {
+                            AlertField::Target => app.alert_kind = app.alert_kind.next(),
+                            AlertField::Sinks => {
+                                app.alert_sink_idx = (app.alert_sink_idx + 1) % NOTIFICATION_SINKS.len();
+                            }
+                            AlertField::NtfyPriority => app.cycle_alert_ntfy_priority(true),
+                            _ => {}
+                        },
+                        KeyCode::Left => match app.alert_field {
+                            AlertField::Target => app.alert_kind = app.alert_kind.prev(),
+                            AlertField::Sinks => {
+                                app.alert_sink_idx =
+                                    (app.alert_sink_idx + NOTIFICATION_SINKS.len() - 1) % NOTIFICATION_SINKS.len();
+                            }
+                            AlertField::NtfyPriority => app.cycle_alert_ntfy_priority(false),
+                            _ => {}
+                        },
+                        KeyCode::Char(' ') if app.alert_field == AlertField::Sinks => {
+                            app.toggle_alert_sink();
+                        }
                         KeyCode::Enter => {
                             if let Ok(price) = app.alert_input_buf.trim().parse::<f64>() {
-                                if let Some(coin) = app.selected_coin() {
+                                if let Some(coin) = app.selected_coin().cloned() {
                                     let coin_id = coin.id.clone();
-                                    let dir_str = match app.alert_direction {
-                                        AlertDirection::Above => "above",
-                                        AlertDirection::Below => "below",
-                                    };
+                                    app.track_coin(coin);
                                     if let Some(ref db) = app.db {
                                         let db = db.lock().await;
-                                        let _ = db.add_alert(&coin_id, price, dir_str);
+                                        let _ = db.add_alert(
+                                            &coin_id,
+                                            app.alert_kind,
+                                            price,
+                                            app.alert_direction,
+                                            &app.alert_sinks,
+                                            &app.alert_ntfy,
+                                        );
                                     }
                                     app.refresh_alerts().await;
                                 }
@@ -333,10 +456,17 @@ async fn run_main_loop(
                             app.alert_input_buf.clear();
                         }
                         KeyCode::Backspace => {
-                            app.alert_input_buf.pop();
+                            if let Some(buf) = app.alert_text_buf() {
+                                buf.pop();
+                            }
                         }
-                        KeyCode::Char(c) if c.is_ascii_digit() || c == '.' => {
-                            app.alert_input_buf.push(c);
+                        // The target only takes numbers; the ntfy rows take any text
+                        KeyCode::Char(c)
+                            if app.alert_field != AlertField::Target || c.is_ascii_digit() || c == '.' =>
+                        {
+                            if let Some(buf) = app.alert_text_buf() {
+                                buf.push(c);
+                            }
                         }
                         _ => {}
                     },
@@ -374,9 +504,10 @@ async fn run_main_loop(
                         }
                         KeyCode::Enter => {
                             if let Ok(amount) = app.input_buf.trim().parse::<f64>() {
-                                if let Some(coin) = app.selected_coin() {
+                                if let Some(coin) = app.selected_coin().cloned() {
                                     let coin_id = coin.id.clone();
                                     let current_price = coin.current_price;
+                                    app.track_coin(coin);
                                     // Auto-record buy price if this is a new holding
                                     let existing = app.holding_for(&coin_id);
                                     let buy_price = if existing <= 0.0 && amount > 0.0 {
@@ -485,6 +616,51 @@ async fn run_main_loop(
                         }
                         _ => {}
                     },
+                    InputMode::Sectors => {
+                        // Row 0 is "All coins", the sectors follow
+                        let len = app.visible_sectors().len() + 1;
+                        match key.code {
+                            KeyCode::Esc if !app.sector_query.is_empty() => {
+                                app.sector_query.clear();
+                                app.sector_selected = 0;
+                            }
+                            KeyCode::Esc => {
+                                app.input_mode = InputMode::Normal;
+                            }
+                            KeyCode::Enter => {
+                                let category = app
+                                    .sector_selected
+                                    .checked_sub(1)
+                                    .and_then(|i| app.visible_sectors().get(i).map(|c| (*c).clone()));
+                                app.input_mode = InputMode::Normal;
+                                app.set_market_category(category, &client).await;
+                            }
+                            KeyCode::Tab => {
+                                app.sector_sort = app.sector_sort.next();
+                            }
+                            KeyCode::Down => {
+                                app.sector_selected = (app.sector_selected + 1).min(len - 1);
+                            }
+                            KeyCode::Up => {
+                                app.sector_selected = app.sector_selected.saturating_sub(1);
+                            }
+                            KeyCode::PageDown => {
+                                app.sector_selected = (app.sector_selected + app.page_height).min(len - 1);
+                            }
+                            KeyCode::PageUp => {
+                                app.sector_selected = app.sector_selected.saturating_sub(app.page_height);
+                            }
+                            KeyCode::Backspace => {
+                                app.sector_query.pop();
+                                app.sector_selected = usize::from(!app.sector_query.is_empty());
+                            }
+                            KeyCode::Char(c) => {
+                                app.sector_query.push(c);
+                                app.sector_selected = 1;
+                            }
+                            _ => {}
+                        }
+                    }
                     InputMode::Normal if app.sort_picking => {
                         app.sort_picking = false;
                         match key.code {
@@ -524,13 +700,59 @@ async fn run_main_loop(
                         KeyCode::Esc | KeyCode::Char('q') => {
                             app.popup_open = false;
                         }
-                        KeyCode::Char('l') | KeyCode::Right => {
+                        KeyCode::Tab => {
                             app.chart_view = app.chart_view.next();
-                            fetch_chart_if_needed(app, &client).await;
+                            app.chart_cursor = None;
+                            app.load_cached_chart().await;
+                        }
+                        KeyCode::BackTab => {
+                            app.chart_view = app.chart_view.prev();
+                            app.chart_cursor = None;
+                            app.load_cached_chart().await;
+                        }
+                        KeyCode::Char('c') => {
+                            app.chart_mode = app.chart_mode.toggle();
+                        }
+                        KeyCode::Char('L') => {
+                            app.toggle_chart_log();
+                        }
+                        KeyCode::Char('m') => {
+                            app.chart_ma = app.chart_ma.next();
+                        }
+                        KeyCode::Char('1') => {
+                            app.chart_indicators.bollinger = !app.chart_indicators.bollinger;
+                        }
+                        KeyCode::Char('2') => {
+                            app.chart_indicators.rsi = !app.chart_indicators.rsi;
+                        }
+                        KeyCode::Char('3') => {
+                            app.chart_indicators.macd = !app.chart_indicators.macd;
+                        }
+                        KeyCode::Char('4') => {
+                            app.chart_indicators.risk = !app.chart_indicators.risk;
+                        }
+                        KeyCode::Char('l') | KeyCode::Right => {
+                            app.move_chart_cursor(true);
                         }
                         KeyCode::Char('h') | KeyCode::Left => {
+                            app.move_chart_cursor(false);
+                        }
+                        _ => {}
+                    },
+                    InputMode::Normal if app.compare_open => match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('C') => {
+                            app.compare_open = false;
+                        }
+                        KeyCode::Tab => {
+                            app.chart_view = app.chart_view.next();
+                            app.load_cached_chart().await;
+                        }
+                        KeyCode::BackTab => {
                             app.chart_view = app.chart_view.prev();
-                            fetch_chart_if_needed(app, &client).await;
+                            app.load_cached_chart().await;
+                        }
+                        KeyCode::Char('L') => {
+                            app.toggle_chart_log();
                         }
                         _ => {}
                     },
@@ -541,6 +763,8 @@ async fn run_main_loop(
                                 app.filter_query.clear();
                                 app.selected = 0;
                                 app.clamp_selection();
+                            } else if app.tab == Tab::Markets && app.market_category.is_some() {
+                                app.set_market_category(None, &client).await;
                             } else {
                                 app.quit = true;
                             }
@@ -556,6 +780,11 @@ async fn run_main_loop(
                             if app.selected_coin().is_some() {
                                 app.alert_input_buf.clear();
                                 app.alert_direction = AlertDirection::Above;
+                                app.alert_kind = AlertKind::Price;
+                                app.alert_sinks.clear();
+                                app.alert_sink_idx = 0;
+                                app.alert_ntfy = AlertNtfy::default();
+                                app.alert_field = AlertField::Target;
                                 app.input_mode = InputMode::EditingAlert;
                             }
                         }
@@ -589,12 +818,46 @@ async fn run_main_loop(
                             app.selected = 0;
                             app.clamp_selection();
                         }
+                        KeyCode::Char('4') => {
+                            app.tab = Tab::Discover;
+                            app.selected = 0;
+                            app.clamp_selection();
+                        }
+                        KeyCode::Char('l') | KeyCode::Right if app.tab == Tab::Discover => {
+                            app.jump_discover_pane(true);
+                        }
+                        KeyCode::Char('h') | KeyCode::Left if app.tab == Tab::Discover => {
+                            app.jump_discover_pane(false);
+                        }
+                        KeyCode::Char('p') if app.tab == Tab::Portfolio => {
+                            app.allocation_open = !app.allocation_open;
+                        }
+                        KeyCode::Char('O') if app.tab == Tab::Portfolio && app.allocation_open => {
+                            app.allocation_group = !app.allocation_group;
+                        }
+                        KeyCode::Char('t') => {
+                            app.treemap = !app.treemap;
+                        }
+                        KeyCode::Char('w') if app.tab == Tab::Discover || app.treemap => {
+                            app.change_window = app.change_window.next();
+                            app.clamp_selection();
+                        }
+                        KeyCode::Char('j') | KeyCode::Down | KeyCode::Char('k') | KeyCode::Up
+                            if app.treemap =>
+                        {
+                            let forward = matches!(key.code, KeyCode::Char('j') | KeyCode::Down);
+                            if let Some(i) = app.next_treemap_block(forward) {
+                                app.selected = i;
+                                app.adjust_scroll();
+                            }
+                        }
                         KeyCode::Char('j') | KeyCode::Down => {
                             let len = app.visible_coins().len();
                             if len > 0 {
                                 app.selected = (app.selected + 1).min(len - 1);
                             }
                             app.adjust_scroll();
+                            load_more_at_end(app, &client).await;
                         }
                         KeyCode::Char('k') | KeyCode::Up => {
                             app.selected = app.selected.saturating_sub(1);
@@ -610,6 +873,7 @@ async fn run_main_loop(
                                     (app.selected + app.page_height).min(len - 1);
                             }
                             app.adjust_scroll();
+                            load_more_at_end(app, &client).await;
                         }
                         KeyCode::PageUp | KeyCode::Char('u')
                             if key.modifiers.contains(KeyModifiers::CONTROL)
@@ -629,17 +893,30 @@ async fn run_main_loop(
                                 app.selected = len - 1;
                             }
                             app.adjust_scroll();
+                            load_more_at_end(app, &client).await;
                         }
                         KeyCode::Enter => {
                             if app.selected_coin().is_some() {
                                 app.popup_open = true;
                                 app.chart_view = ChartView::Day1;
-                                fetch_chart_if_needed(app, &client).await;
+                                app.chart_cursor = None;
+                                app.load_cached_chart().await;
+                            }
+                        }
+                        KeyCode::Char('x') => {
+                            app.toggle_compare();
+                        }
+                        KeyCode::Char('C') => {
+                            if !app.compare_ids.is_empty() {
+                                app.compare_open = true;
+                                app.chart_view = ChartView::Day7;
+                                app.load_cached_chart().await;
                             }
                         }
                         KeyCode::Char('f') => {
-                            if let Some(coin) = app.selected_coin() {
+                            if let Some(coin) = app.selected_coin().cloned() {
                                 let coin_id = coin.id.clone();
+                                app.track_coin(coin);
                                 if let Some(ref db) = app.db {
                                     let db = db.lock().await;
                                     let _ = db.toggle_favourite(&coin_id);
@@ -671,17 +948,35 @@ async fn run_main_loop(
                                 app.clamp_selection();
                             }
                         }
+                        KeyCode::Char('D') => {
+                            if let Some(coin) = app.selected_coin() {
+                                let ids: Vec<i64> = app.alerts.iter()
+                                    .filter(|a| a.coin_id == coin.id && !a.triggered)
+                                    .map(|a| a.id)
+                                    .collect();
+                                if let Some(ref db) = app.db {
+                                    let db = db.lock().await;
+                                    for id in ids {
+                                        let _ = db.delete_alert(id);
+                                    }
+                                }
+                                app.refresh_alerts().await;
+                            }
+                        }
                         KeyCode::Char('r') => {
                             app.loading = true;
                             app.refresh_market_data(&client).await;
                             app.refresh_db_state().await;
                             app.clamp_selection();
-                            app.check_alerts();
+                            app.check_alerts().await;
                             app.refresh_global_stats(&client).await;
                         }
                         KeyCode::Char('S') => {
                             app.open_settings();
                         }
+                        KeyCode::Char('o') => {
+                            app.open_sectors();
+                        }
                         KeyCode::Char('c') => {
                             app.search_query.clear();
                             app.search_results.clear();
@@ -704,6 +999,14 @@ async fn run_main_loop(
     Ok(())
 }
 
+/// Fetch the next Markets page once the selection reaches the last row.
+async fn load_more_at_end(app: &mut App, client: &CoinGeckoClient) {
+    let len = app.visible_coins().len();
+    if app.can_load_more() && app.selected + 1 >= len {
+        app.load_more_markets(client).await;
+    }
+}
+
 fn toggle_sort(app: &mut App, col: SortColumn) {
     if app.sort_column == Some(col) {
         match app.sort_direction {
@@ -719,6 +1022,7 @@ fn toggle_sort(app: &mut App, col: SortColumn) {
     }
 }
 
+#[allow(clippy::collapsible_match)]
 async fn handle_settings_key(app: &mut App, key: KeyCode, client: &mut CoinGeckoClient) {
     if app.settings_editing {
         match key {
@@ -756,13 +1060,26 @@ async fn handle_settings_key(app: &mut App, key: KeyCode, client: &mut CoinGecko
             KeyCode::Enter | KeyCode::Char('e') => {
                 if app.settings_field.is_text_field() {
                     app.settings_editing = true;
+                } else if app.settings_field == SettingsField::Notifications {
+                    app.toggle_selected_sink();
+                } else if app.settings_field == SettingsField::TestNotification {
+                    app.send_test_notification();
                 }
             }
+            KeyCode::Char(' ') if app.settings_field == SettingsField::Notifications => {
+                app.toggle_selected_sink();
+            }
             KeyCode::Char('h') | KeyCode::Left => {
                 match app.settings_field {
                     SettingsField::Currency => app.cycle_currency(false),
                     SettingsField::Theme => app.cycle_theme(false),
+                    SettingsField::MarketsLimit => app.cycle_markets_limit(false),
+                    SettingsField::LazyLoad => app.settings_lazy_load = !app.settings_lazy_load,
                     SettingsField::Notifications => app.cycle_notification(false),
+                    SettingsField::NtfyPriority => app.cycle_ntfy_priority(false),
+                    SettingsField::WebhookMethod => app.cycle_webhook_method(false),
+                    SettingsField::SmtpSecurity => app.cycle_smtp_security(false),
+                    SettingsField::QuietMode => app.cycle_quiet_mode(false),
                     _ => {}
                 }
             }
@@ -770,7 +1087,13 @@ async fn handle_settings_key(app: &mut App, key: KeyCode, client: &mut CoinGecko
                 match app.settings_field {
                     SettingsField::Currency => app.cycle_currency(true),
                     SettingsField::Theme => app.cycle_theme(true),
+                    SettingsField::MarketsLimit => app.cycle_markets_limit(true),
+                    SettingsField::LazyLoad => app.settings_lazy_load = !app.settings_lazy_load,
                     SettingsField::Notifications => app.cycle_notification(true),
+                    SettingsField::NtfyPriority => app.cycle_ntfy_priority(true),
+                    SettingsField::WebhookMethod => app.cycle_webhook_method(true),
+                    SettingsField::SmtpSecurity => app.cycle_smtp_security(true),
+                    SettingsField::QuietMode => app.cycle_quiet_mode(true),
                     _ => {}
                 }
             }
@@ -779,32 +1102,42 @@ async fn handle_settings_key(app: &mut App, key: KeyCode, client: &mut CoinGecko
                 let new_currency = CURRENCIES[app.settings_currency_idx].to_string();
                 let new_theme_name = theme::THEME_NAMES[app.settings_theme_idx].to_string();
                 let currency_changed = new_currency != app.config.currency;
-                let new_notif = NOTIFICATION_METHODS[app.settings_notification_idx];
+                let new_limit = MARKET_LIMITS[app.settings_markets_idx];
+                let markets_changed =
+                    new_limit != app.config.markets_limit || app.settings_lazy_load != app.config.lazy_load;
 
                 if let Some(ref db) = app.db {
                     let db = db.lock().await;
                     let _ = db.set_setting("coingecko_api_key", &app.settings_coingecko_key);
                     let _ = db.set_setting("cmc_api_key", &app.settings_cmc_key);
                     let _ = db.set_setting("currency", &new_currency);
-                    let _ = db.set_setting("notification_method", new_notif);
-                    let _ = db.set_setting("ntfy_topic", &app.settings_ntfy_topic);
+                    if let Err(e) = app.settings_notifications.save(&db) {
+                        app::log_error(&format!("Settings: {}", e));
+                    }
                 }
                 app.coingecko_api_key = app.settings_coingecko_key.clone();
                 app.cmc_api_key = app.settings_cmc_key.clone();
                 app.config.currency = new_currency;
                 app.config.theme = new_theme_name.clone();
+                app.config.markets_limit = new_limit;
+                app.config.lazy_load = app.settings_lazy_load;
                 app.theme = theme::by_name(&new_theme_name);
-                app.notification_method = notification_method_from_str(new_notif);
-                app.ntfy_topic = app.settings_ntfy_topic.clone();
+                app.notifications = app.settings_notifications.clone();
+                app.rebuild_sinks();
                 let _ = app.config.save();
 
                 // Recreate client with new key/currency
                 *client = CoinGeckoClient::new(&app.config.currency, &app.coingecko_api_key);
                 app.chart_cache.clear();
+                app.chart_synced.clear();
+                app.ohlc_cache.clear();
+                app.ohlc_synced.clear();
 
-                if currency_changed {
+                if currency_changed || markets_changed {
                     app.loading = true;
+                    app.markets_loaded = 0;
                     app.refresh_market_data(client).await;
+                    app.clamp_selection();
                 }
 
                 app.input_mode = InputMode::Normal;
@@ -813,26 +1146,3 @@ async fn handle_settings_key(app: &mut App, key: KeyCode, client: &mut CoinGecko
         }
     }
 }
-
-async fn fetch_chart_if_needed(app: &mut App, client: &CoinGeckoClient) {
-    let (coin_id, days) = match app.selected_coin() {
-        Some(c) => (c.id.clone(), app.chart_view.days()),
-        None => return,
-    };
-    let key = (coin_id.clone(), days);
-    if app.chart_cache.contains_key(&key) {
-        return;
-    }
-
-    app.loading_chart = true;
-
-    match client.fetch_price_history(&coin_id, days).await {
-        Ok(history) => {
-            app.chart_cache.insert(key, history);
-        }
-        Err(e) => {
-            app.set_error(format!("Chart: {}", e));
-        }
-    }
-    app.loading_chart = false;
-}
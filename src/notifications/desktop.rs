@@ -0,0 +1,29 @@
+use anyhow::Context;
+
+use super::{AlertMessage, NotificationSink, SendFuture};
+
+pub struct DesktopSink;
+
+impl NotificationSink for DesktopSink {
+    fn name(&self) -> &'static str {
+        "desktop"
+    }
+
+    fn send<'a>(&'a self, msg: &'a AlertMessage) -> SendFuture<'a> {
+        let title = msg.title.clone();
+        let body = msg.body.clone();
+        Box::pin(async move {
+            // notify-rust talks to the session bus synchronously
+            tokio::task::spawn_blocking(move || {
+                notify_rust::Notification::new()
+                    .summary(&title)
+                    .body(&body)
+                    .timeout(notify_rust::Timeout::Milliseconds(5000))
+                    .show()
+                    .map(|_| ())
+            })
+            .await?
+            .context("Failed to show desktop notification")
+        })
+    }
+}
@@ -0,0 +1,108 @@
+use anyhow::Context;
+use serde_json::json;
+
+use super::{coin_url, direction_arrow, error_excerpt, AlertMessage, NotificationSink, SendFuture};
+use crate::types::AlertDirection;
+
+pub struct DiscordSink {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl DiscordSink {
+    pub fn new(client: reqwest::Client, webhook_url: &str) -> Self {
+        Self {
+            client,
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+}
+
+impl NotificationSink for DiscordSink {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn send<'a>(&'a self, msg: &'a AlertMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            let color = match msg.direction {
+                AlertDirection::Above => 0x2ecc71,
+                AlertDirection::Below => 0xe74c3c,
+            };
+            let payload = if msg.digest {
+                json!({
+                    "username": "bags",
+                    "embeds": [{ "title": msg.title, "description": format!("```\n{}\n```", msg.body) }],
+                })
+            } else {
+                json!({
+                "username": "bags",
+                "embeds": [{
+                    "title": format!(
+                        "{} {} ({})",
+                        direction_arrow(msg.direction),
+                        msg.coin_name,
+                        msg.coin_symbol.to_uppercase()
+                    ),
+                    "url": coin_url(&msg.coin_id),
+                    "description": msg.body,
+                    "color": color,
+                    "fields": [
+                        { "name": "Now", "value": msg.current_text(), "inline": true },
+                        { "name": "Target", "value": msg.target_text(), "inline": true },
+                        { "name": "Alert", "value": msg.kind.label(), "inline": true },
+                    ],
+                }],
+                })
+            };
+            let resp = self
+                .client
+                .post(&self.webhook_url)
+                .json(&payload)
+                .send()
+                .await
+                .context("Failed to reach Discord")?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                anyhow::bail!("Discord error {}: {}", status, error_excerpt(&body));
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::test_support::{mock_http, sample_alert};
+
+    #[tokio::test]
+    async fn posts_embed_to_webhook() {
+        let (base, server) = mock_http(204, "").await;
+        let url = format!("{}/api/webhooks/1/token", base);
+        DiscordSink::new(reqwest::Client::new(), &url).send(&sample_alert()).await.unwrap();
+
+        let req = server.await.unwrap();
+        assert_eq!(req.request_line, "POST /api/webhooks/1/token");
+        let json = req.json();
+        assert_eq!(json["username"], "bags");
+        let embed = &json["embeds"][0];
+        assert_eq!(embed["title"], "\u{25b2} Bitcoin (BTC)");
+        assert_eq!(embed["url"], "https://www.coingecko.com/en/coins/bitcoin");
+        assert_eq!(embed["color"], 0x2ecc71);
+        assert_eq!(embed["fields"][0]["value"], "$70,123.45");
+        assert_eq!(embed["fields"][1]["value"], "$70,000.00");
+    }
+
+    #[tokio::test]
+    async fn reports_error_body() {
+        let (base, server) = mock_http(404, r#"{"message":"Unknown Webhook","code":10015}"#).await;
+        let url = format!("{}/api/webhooks/1/token", base);
+        let err = DiscordSink::new(reqwest::Client::new(), &url).send(&sample_alert()).await.unwrap_err();
+        server.await.unwrap();
+        let err = format!("{:#}", err);
+        assert!(err.starts_with("Discord error 404"));
+        assert!(err.contains("Unknown Webhook"));
+    }
+}
@@ -0,0 +1,257 @@
+use anyhow::{Context, Result};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use super::{direction_str, html_escape, AlertMessage, NotificationSink, SendFuture};
+use crate::db::Db;
+
+/// Connection security: plain (e.g. a local SMTP catcher), STARTTLS upgrade, or implicit TLS.
+pub const SMTP_SECURITY: &[&str] = &["starttls", "tls", "none"];
+
+#[derive(Debug, Clone, Default)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// Empty means the default port for `security`
+    pub port: String,
+    pub security: String,
+    pub username: String,
+    pub password: String,
+    pub from: String,
+    /// Comma separated recipients
+    pub to: String,
+}
+
+impl SmtpConfig {
+    pub fn load(db: &Db) -> Self {
+        Self {
+            host: db.get_setting("smtp_host").unwrap_or_default(),
+            port: db.get_setting("smtp_port").unwrap_or_default(),
+            security: db
+                .get_setting("smtp_security")
+                .unwrap_or_else(|| SMTP_SECURITY[0].to_string()),
+            username: db.get_setting("smtp_username").unwrap_or_default(),
+            password: db.get_setting("smtp_password").unwrap_or_default(),
+            from: db.get_setting("smtp_from").unwrap_or_default(),
+            to: db.get_setting("smtp_to").unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self, db: &Db) -> Result<()> {
+        db.set_setting("smtp_host", &self.host)?;
+        db.set_setting("smtp_port", &self.port)?;
+        db.set_setting("smtp_security", &self.security)?;
+        db.set_setting("smtp_username", &self.username)?;
+        db.set_setting("smtp_password", &self.password)?;
+        db.set_setting("smtp_from", &self.from)?;
+        db.set_setting("smtp_to", &self.to)?;
+        Ok(())
+    }
+
+    pub fn security_idx(&self) -> usize {
+        SMTP_SECURITY
+            .iter()
+            .position(|s| *s == self.security)
+            .unwrap_or(0)
+    }
+
+    pub fn is_configured(&self) -> bool {
+        !self.host.is_empty() && !self.from.is_empty() && !self.to.is_empty()
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let mut builder = match self.security.as_str() {
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host),
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?,
+            _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)?,
+        };
+        if !self.port.is_empty() {
+            let port: u16 = self.port.trim().parse().context("Invalid SMTP port")?;
+            builder = builder.port(port);
+        }
+        if !self.username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                self.username.clone(),
+                self.password.clone(),
+            ));
+        }
+        Ok(builder.build())
+    }
+}
+
+pub struct EmailSink {
+    config: SmtpConfig,
+}
+
+impl EmailSink {
+    pub fn new(config: SmtpConfig) -> Self {
+        Self { config }
+    }
+
+    fn build_message(&self, msg: &AlertMessage) -> Result<Message> {
+        let from: Mailbox = self.config.from.parse().context("Invalid From address")?;
+        let mut builder = Message::builder().from(from).subject(&msg.title);
+        for to in self.config.to.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            builder = builder.to(to.parse().with_context(|| format!("Invalid To address '{}'", to))?);
+        }
+        let body = MultiPart::alternative_plain_html(plain_body(msg), html_body(msg));
+        Ok(builder.multipart(body)?)
+    }
+}
+
+impl NotificationSink for EmailSink {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn send<'a>(&'a self, msg: &'a AlertMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            let email = self.build_message(msg)?;
+            self.config
+                .transport()?
+                .send(email)
+                .await
+                .context("SMTP delivery failed")?;
+            Ok(())
+        })
+    }
+}
+
+fn plain_body(msg: &AlertMessage) -> String {
+    if msg.digest {
+        return format!("{}\n", msg.body);
+    }
+    format!(
+        "{}\n\nCoin:      {} ({})\nAlert:     {}\nDirection: {}\nTarget:    {}\nCurrent:   {}\n",
+        msg.body,
+        msg.coin_name,
+        msg.coin_symbol.to_uppercase(),
+        msg.kind.label(),
+        direction_str(msg.direction),
+        msg.target_text(),
+        msg.current_text(),
+    )
+}
+
+fn html_body(msg: &AlertMessage) -> String {
+    let row = |k: &str, v: &str| {
+        format!(
+            "<tr><td style=\"padding:2px 12px 2px 0;color:#666\">{}</td><td>{}</td></tr>",
+            k,
+            html_escape(v)
+        )
+    };
+    if msg.digest {
+        return format!(
+            "<html><body style=\"font-family:sans-serif\"><h3>{}</h3><pre>{}</pre></body></html>",
+            html_escape(&msg.title),
+            html_escape(&msg.body),
+        );
+    }
+    format!(
+        "<html><body style=\"font-family:sans-serif\"><h3>{}</h3><p>{}</p><table>{}{}{}{}{}</table></body></html>",
+        html_escape(&msg.title),
+        html_escape(&msg.body),
+        row("Coin", &format!("{} ({})", msg.coin_name, msg.coin_symbol.to_uppercase())),
+        row("Alert", msg.kind.label()),
+        row("Direction", direction_str(msg.direction)),
+        row("Target", &msg.target_text()),
+        row("Current", &msg.current_text()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::test_support::sample_alert;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    fn config(port: u16) -> SmtpConfig {
+        SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port: port.to_string(),
+            security: "none".to_string(),
+            from: "bags <bags@example.com>".to_string(),
+            to: "alice@example.com, bob@example.com".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Accept one SMTP session and return everything the client sent.
+    async fn smtp_catcher() -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            stream.get_mut().write_all(b"220 catcher ready\r\n").await.unwrap();
+            let mut transcript = String::new();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if stream.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                transcript.push_str(&line);
+                let reply: &[u8] = if in_data {
+                    if line != ".\r\n" {
+                        continue;
+                    }
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else if line.starts_with("DATA") {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line.starts_with("QUIT") {
+                    stream.get_mut().write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                stream.get_mut().write_all(reply).await.unwrap();
+            }
+            transcript
+        });
+        (port, handle)
+    }
+
+    #[test]
+    fn message_has_plain_and_html_parts() {
+        let mut msg = sample_alert();
+        msg.body = "BTC <b>broke</b> out & up".to_string();
+        let email = EmailSink::new(config(25)).build_message(&msg).unwrap();
+        let raw = String::from_utf8(email.formatted()).unwrap();
+
+        assert!(raw.contains("Subject: bags: Bitcoin alert"));
+        assert!(raw.contains("To: alice@example.com, bob@example.com"));
+        assert!(raw.contains("multipart/alternative"));
+        assert!(raw.contains("Content-Type: text/plain"));
+        assert!(raw.contains("Content-Type: text/html"));
+        // Plain text as written, HTML escaped
+        assert!(raw.contains("BTC <b>broke</b> out & up"));
+        assert!(raw.contains("BTC &lt;b&gt;broke&lt;/b&gt; out &amp; up"));
+        assert!(raw.contains("Target:    $70,000.00"));
+    }
+
+    #[test]
+    fn invalid_addresses_are_rejected() {
+        let mut cfg = config(25);
+        cfg.to = "alice@example.com, not an address".to_string();
+        let err = EmailSink::new(cfg).build_message(&sample_alert()).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid To address 'not an address'"));
+    }
+
+    #[tokio::test]
+    async fn delivers_to_a_local_catcher_without_tls() {
+        let (port, catcher) = smtp_catcher().await;
+        EmailSink::new(config(port)).send(&sample_alert()).await.unwrap();
+        let transcript = catcher.await.unwrap();
+
+        assert!(transcript.contains("MAIL FROM:<bags@example.com>"));
+        assert!(transcript.contains("RCPT TO:<alice@example.com>"));
+        assert!(transcript.contains("RCPT TO:<bob@example.com>"));
+        assert!(transcript.contains("Subject: bags: Bitcoin alert"));
+        assert!(!transcript.contains("STARTTLS"));
+    }
+}
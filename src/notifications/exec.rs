@@ -0,0 +1,120 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::{direction_str, AlertMessage, NotificationSink, SendFuture};
+use crate::app::log_error;
+use crate::db::Db;
+
+const DEFAULT_EXEC_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Default)]
+pub struct ExecConfig {
+    /// Shell command run for every alert
+    pub command: String,
+    /// Seconds before the command is killed; empty means `DEFAULT_EXEC_TIMEOUT_SECS`
+    pub timeout_secs: String,
+}
+
+impl ExecConfig {
+    pub fn load(db: &Db) -> Self {
+        Self {
+            command: db.get_setting("exec_command").unwrap_or_default(),
+            timeout_secs: db.get_setting("exec_timeout_secs").unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self, db: &Db) -> Result<()> {
+        db.set_setting("exec_command", &self.command)?;
+        db.set_setting("exec_timeout_secs", &self.timeout_secs)?;
+        Ok(())
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.timeout_secs
+                .trim()
+                .parse()
+                .unwrap_or(DEFAULT_EXEC_TIMEOUT_SECS),
+        )
+    }
+}
+
+/// Runs a user command with the alert in `BAGS_*` environment variables and
+/// as JSON on stdin.
+pub struct ExecSink {
+    config: ExecConfig,
+}
+
+impl ExecSink {
+    pub fn new(config: ExecConfig) -> Self {
+        Self { config }
+    }
+
+    fn shell(&self) -> Command {
+        if cfg!(windows) {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(&self.config.command);
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg(&self.config.command);
+            cmd
+        }
+    }
+}
+
+impl NotificationSink for ExecSink {
+    fn name(&self) -> &'static str {
+        "exec"
+    }
+
+    fn send<'a>(&'a self, msg: &'a AlertMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            let mut child = self
+                .shell()
+                .env("BAGS_COIN_ID", &msg.coin_id)
+                .env("BAGS_COIN_NAME", &msg.coin_name)
+                .env("BAGS_KIND", msg.kind_str())
+                .env("BAGS_TARGET", msg.target.to_string())
+                .env("BAGS_PRICE", msg.current.to_string())
+                .env("BAGS_DIRECTION", direction_str(msg.direction))
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()
+                .context("Failed to start exec command")?;
+
+            // Writing stdin counts against the timeout too, in case the
+            // command never reads it
+            let json = serde_json::to_vec(msg)?;
+            let stdin = child.stdin.take();
+            let run = async move {
+                if let Some(mut stdin) = stdin {
+                    // A command that ignores stdin may close it early; that's fine
+                    let _ = stdin.write_all(&json).await;
+                }
+                child.wait_with_output().await
+            };
+
+            let timeout = self.config.timeout();
+            let output = tokio::time::timeout(timeout, run)
+                .await
+                .with_context(|| format!("Exec command timed out after {}s", timeout.as_secs()))??;
+
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            if !output.status.success() {
+                anyhow::bail!("Exec command exited with {}: {}", output.status, stderr);
+            }
+            if !stderr.is_empty() {
+                log_error(&format!("Exec command stderr: {}", stderr));
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,498 @@
+mod desktop;
+mod discord;
+mod email;
+mod exec;
+mod ntfy;
+mod outbox;
+mod quiet;
+mod slack;
+mod telegram;
+mod template;
+#[cfg(test)]
+mod test_support;
+mod webhook;
+
+pub use desktop::DesktopSink;
+pub use discord::DiscordSink;
+pub use email::{EmailSink, SmtpConfig, SMTP_SECURITY};
+pub use exec::{ExecConfig, ExecSink};
+pub use ntfy::{NtfyConfig, NtfySink, NTFY_PRIORITIES};
+pub use quiet::{QuietDecision, QuietHours, QUIET_MODES};
+pub use slack::SlackSink;
+pub use telegram::{TelegramConfig, TelegramSink};
+pub use template::{MessageTemplates, TEMPLATE_PLACEHOLDERS};
+pub use webhook::{WebhookConfig, WebhookSink, WEBHOOK_METHODS};
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex;
+
+use crate::db::Db;
+use crate::types::{
+    currency_symbol, AlertDirection, AlertKind, AlertNtfy, Coin, PriceAlert, NOTIFICATION_SINKS,
+};
+use crate::ui::format_price;
+use template::TemplateVars;
+
+/// Everything a sink needs to describe a fired alert.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertMessage {
+    pub title: String,
+    pub body: String,
+    pub coin_id: String,
+    pub coin_name: String,
+    pub coin_symbol: String,
+    pub kind: AlertKind,
+    /// Price, volume multiple or rank depending on `kind`
+    pub target: f64,
+    pub current: f64,
+    pub direction: AlertDirection,
+    /// A scheduled portfolio digest rather than a coin alert; only `title`
+    /// and `body` are meaningful
+    #[serde(default)]
+    pub digest: bool,
+    /// `current` and `target` formatted for people, e.g. "$0.000012"
+    #[serde(default)]
+    pub price_display: String,
+    #[serde(default)]
+    pub target_display: String,
+    /// The alert's own ntfy headers
+    #[serde(default)]
+    pub ntfy: AlertNtfy,
+}
+
+impl AlertMessage {
+    /// Describe a fired alert, using the user's templates when set.
+    pub fn for_alert(
+        alert: &PriceAlert,
+        coin: &Coin,
+        observed: f64,
+        currency: &str,
+        templates: &MessageTemplates,
+    ) -> Self {
+        let (kind, target, direction) = (alert.kind, alert.target_price, alert.direction);
+        let dir_str = direction_str(direction);
+        let sym = currency_symbol(currency);
+        let (price_display, target_display) = match kind {
+            AlertKind::Price => (
+                format!("{}{}", sym, format_price(observed)),
+                format!("{}{}", sym, format_price(target)),
+            ),
+            AlertKind::VolumeSpike => (format!("{:.1}x", observed), format!("{:.1}x", target)),
+            AlertKind::Rank => (format!("#{}", observed as u32), format!("top {}", target as u32)),
+        };
+        let distance = match kind {
+            AlertKind::Rank => String::new(),
+            _ if target != 0.0 => format!("{:+.2}%", (observed - target) / target * 100.0),
+            _ => String::new(),
+        };
+        let time = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+        let vars = TemplateVars {
+            coin: &coin.name,
+            symbol: &coin.symbol.to_uppercase(),
+            kind: kind.label(),
+            price: &price_display,
+            target: &target_display,
+            direction: dir_str,
+            distance: &distance,
+            time: &time,
+        };
+
+        let title = if templates.title.trim().is_empty() {
+            format!("bags: {} alert", coin.name)
+        } else {
+            template::render(&templates.title, &vars)
+        };
+        let body = if !templates.body.trim().is_empty() {
+            template::render(&templates.body, &vars)
+        } else {
+            match kind {
+                AlertKind::Price => format!(
+                    "{} hit {} target {} (now {})",
+                    coin.name, dir_str, target_display, price_display
+                ),
+                AlertKind::VolumeSpike => format!(
+                    "{} volume at {} its 24h average (threshold {})",
+                    coin.name, price_display, target_display
+                ),
+                AlertKind::Rank => format!(
+                    "{} {} the {} (now {})",
+                    coin.name,
+                    rank_verb(direction),
+                    target_display,
+                    price_display
+                ),
+            }
+        };
+        Self {
+            title,
+            body,
+            coin_id: coin.id.clone(),
+            coin_name: coin.name.clone(),
+            coin_symbol: coin.symbol.clone(),
+            kind,
+            target,
+            current: observed,
+            direction,
+            digest: false,
+            price_display,
+            target_display,
+            ntfy: alert.ntfy.clone(),
+        }
+    }
+
+    pub fn digest(title: String, body: String) -> Self {
+        Self {
+            title,
+            body,
+            coin_id: String::new(),
+            coin_name: String::new(),
+            coin_symbol: String::new(),
+            kind: AlertKind::Price,
+            target: 0.0,
+            current: 0.0,
+            direction: AlertDirection::Above,
+            digest: true,
+            price_display: String::new(),
+            target_display: String::new(),
+            ntfy: AlertNtfy::default(),
+        }
+    }
+
+    /// The current value as shown to people, for payloads queued before
+    /// display strings existed too.
+    pub fn current_text(&self) -> String {
+        if self.price_display.is_empty() {
+            self.current.to_string()
+        } else {
+            self.price_display.clone()
+        }
+    }
+
+    pub fn target_text(&self) -> String {
+        if self.target_display.is_empty() {
+            self.target.to_string()
+        } else {
+            self.target_display.clone()
+        }
+    }
+
+    /// `"digest"` or the alert kind, for machine-readable payloads.
+    pub fn kind_str(&self) -> &'static str {
+        if self.digest {
+            "digest"
+        } else {
+            self.kind.as_str()
+        }
+    }
+}
+
+pub fn direction_str(direction: AlertDirection) -> &'static str {
+    match direction {
+        AlertDirection::Above => "above",
+        AlertDirection::Below => "below",
+    }
+}
+
+/// What a rank bracket alert saw the coin do.
+pub fn rank_verb(direction: AlertDirection) -> &'static str {
+    match direction {
+        AlertDirection::Above => "entered",
+        AlertDirection::Below => "left",
+    }
+}
+
+pub fn direction_arrow(direction: AlertDirection) -> &'static str {
+    match direction {
+        AlertDirection::Above => "\u{25b2}",
+        AlertDirection::Below => "\u{25bc}",
+    }
+}
+
+pub fn coin_url(coin_id: &str) -> String {
+    format!("https://www.coingecko.com/en/coins/{}", coin_id)
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// One client for all the HTTP sinks.
+fn http_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+/// Replace each `{name}` in `template` with `value(name)` in one left-to-right
+/// pass, so substituted text is never scanned for placeholders again.
+/// Braces that don't form a known placeholder are kept as written.
+fn fill_placeholders(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let name = rest[1..].find('}').map(|close| &rest[1..1 + close]);
+        match name.and_then(|n| value(n).map(|v| (n.len(), v))) {
+            Some((len, v)) => {
+                out.push_str(&v);
+                rest = &rest[len + 2..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The start of an error response body, cut on a character boundary.
+fn error_excerpt(body: &str) -> String {
+    body.chars().take(200).collect()
+}
+
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// A delivery channel for alert notifications.
+pub trait NotificationSink: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn send<'a>(&'a self, msg: &'a AlertMessage) -> SendFuture<'a>;
+}
+
+/// Notification configuration, stored in the encrypted `settings` table.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationSettings {
+    /// Enabled sink names, a subset of `NOTIFICATION_SINKS`
+    pub sinks: Vec<String>,
+    pub ntfy: NtfyConfig,
+    pub webhook: WebhookConfig,
+    pub smtp: SmtpConfig,
+    pub telegram: TelegramConfig,
+    pub discord_url: String,
+    pub slack_url: String,
+    pub exec: ExecConfig,
+    pub quiet: QuietHours,
+    pub templates: MessageTemplates,
+}
+
+impl NotificationSettings {
+    pub fn load(db: &Db) -> Self {
+        let sinks = match db.get_setting("notification_sinks") {
+            Some(list) => list
+                .split(',')
+                .map(str::trim)
+                .filter(|s| NOTIFICATION_SINKS.contains(s))
+                .map(String::from)
+                .collect(),
+            // Migrate from the old single-choice setting
+            None => match db.get_setting("notification_method").as_deref() {
+                Some("desktop") => vec!["desktop".to_string()],
+                Some("ntfy") => vec!["ntfy".to_string()],
+                Some("both") => vec!["desktop".to_string(), "ntfy".to_string()],
+                _ => Vec::new(),
+            },
+        };
+        Self {
+            sinks,
+            ntfy: NtfyConfig::load(db),
+            webhook: WebhookConfig::load(db),
+            smtp: SmtpConfig::load(db),
+            telegram: TelegramConfig::load(db),
+            discord_url: db.get_setting("discord_webhook_url").unwrap_or_default(),
+            slack_url: db.get_setting("slack_webhook_url").unwrap_or_default(),
+            exec: ExecConfig::load(db),
+            quiet: QuietHours::load(db),
+            templates: MessageTemplates::load(db),
+        }
+    }
+
+    pub fn save(&self, db: &Db) -> Result<()> {
+        db.set_setting("notification_sinks", &self.sinks.join(","))?;
+        db.set_setting("notification_method", "")?;
+        self.ntfy.save(db)?;
+        self.webhook.save(db)?;
+        self.smtp.save(db)?;
+        self.telegram.save(db)?;
+        db.set_setting("discord_webhook_url", &self.discord_url)?;
+        db.set_setting("slack_webhook_url", &self.slack_url)?;
+        self.exec.save(db)?;
+        self.quiet.save(db)?;
+        self.templates.save(db)?;
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.sinks.iter().any(|s| s == name)
+    }
+
+    pub fn toggle(&mut self, name: &str) {
+        if self.is_enabled(name) {
+            self.sinks.retain(|s| s != name);
+        } else {
+            self.sinks.push(name.to_string());
+        }
+    }
+
+    /// Instantiate the enabled sinks, skipping any that are missing required config.
+    pub fn build_sinks(&self) -> Result<Vec<Arc<dyn NotificationSink>>> {
+        self.build_named(self.sinks.iter().map(String::as_str))
+    }
+
+    /// Instantiate every configured sink, enabled or not, so alerts routed
+    /// to a specific sink and older outbox entries can still be delivered.
+    pub fn build_all_sinks(&self) -> Result<Vec<Arc<dyn NotificationSink>>> {
+        self.build_named(NOTIFICATION_SINKS.iter().copied())
+    }
+
+    /// Pick the sinks for one alert: its own routing if it has any, otherwise
+    /// the globally enabled ones.
+    pub fn route(
+        &self,
+        sinks: &[Arc<dyn NotificationSink>],
+        routing: &[String],
+    ) -> Vec<Arc<dyn NotificationSink>> {
+        sinks
+            .iter()
+            .filter(|s| {
+                if routing.is_empty() {
+                    self.is_enabled(s.name())
+                } else {
+                    routing.iter().any(|r| r == s.name())
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn build_named<'a>(&self, names: impl Iterator<Item = &'a str>) -> Result<Vec<Arc<dyn NotificationSink>>> {
+        let client = http_client()?;
+        let mut out: Vec<Arc<dyn NotificationSink>> = Vec::new();
+        for name in names {
+            match name {
+                "desktop" => out.push(Arc::new(DesktopSink)),
+                "ntfy" if !self.ntfy.topic.is_empty() => {
+                    out.push(Arc::new(NtfySink::new(client.clone(), self.ntfy.clone())));
+                }
+                "webhook" if !self.webhook.url.is_empty() => {
+                    out.push(Arc::new(WebhookSink::new(client.clone(), self.webhook.clone())));
+                }
+                "email" if self.smtp.is_configured() => {
+                    out.push(Arc::new(EmailSink::new(self.smtp.clone())));
+                }
+                "telegram" if self.telegram.is_configured() => {
+                    out.push(Arc::new(TelegramSink::new(client.clone(), self.telegram.clone())));
+                }
+                "discord" if !self.discord_url.is_empty() => {
+                    out.push(Arc::new(DiscordSink::new(client.clone(), &self.discord_url)));
+                }
+                "slack" if !self.slack_url.is_empty() => {
+                    out.push(Arc::new(SlackSink::new(client.clone(), &self.slack_url)));
+                }
+                "exec" if !self.exec.command.is_empty() => {
+                    out.push(Arc::new(ExecSink::new(self.exec.clone())));
+                }
+                _ => {}
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Results reported back to the UI from background deliveries.
+#[derive(Debug, Clone)]
+pub enum DeliveryEvent {
+    /// An outbox entry ran out of retries
+    Failed { sink: String, error: String },
+    /// Per-sink outcome of a test notification; `None` means delivered
+    TestFinished(Vec<(String, Option<String>)>),
+}
+
+/// Queue `msg` for every sink in the persistent outbox and start delivering,
+/// unless quiet hours hold it back.
+pub fn send_alert(
+    db: Arc<Mutex<Db>>,
+    sinks: &[Arc<dyn NotificationSink>],
+    msg: AlertMessage,
+    quiet: &QuietHours,
+    events: UnboundedSender<DeliveryEvent>,
+) {
+    if sinks.is_empty() {
+        return;
+    }
+    let sinks = sinks.to_vec();
+    let quiet = quiet.clone();
+    tokio::spawn(async move {
+        queue_and_deliver(&db, &sinks, &msg, &quiet, &events).await;
+    });
+}
+
+/// Queue `msg` in the outbox and wait for the first delivery attempt. During
+/// quiet hours it is either queued for later or dropped.
+pub async fn queue_and_deliver(
+    db: &Arc<Mutex<Db>>,
+    sinks: &[Arc<dyn NotificationSink>],
+    msg: &AlertMessage,
+    quiet: &QuietHours,
+    events: &UnboundedSender<DeliveryEvent>,
+) {
+    let now = chrono::Local::now();
+    match quiet.check(now) {
+        QuietDecision::Send => {
+            outbox::enqueue(&*db.lock().await, sinks, msg, now.timestamp());
+            outbox::deliver_due(db, sinks, events).await;
+        }
+        QuietDecision::Hold(until) => outbox::enqueue(&*db.lock().await, sinks, msg, until),
+        QuietDecision::Drop => {}
+    }
+}
+
+/// Retry any outbox entries whose backoff has expired.
+pub fn flush_outbox(
+    db: Arc<Mutex<Db>>,
+    sinks: &[Arc<dyn NotificationSink>],
+    events: UnboundedSender<DeliveryEvent>,
+) {
+    let sinks = sinks.to_vec();
+    tokio::spawn(async move {
+        outbox::deliver_due(&db, &sinks, &events).await;
+    });
+}
+
+/// Send a test message straight to each sink, bypassing the outbox.
+pub fn send_test(sinks: Vec<Arc<dyn NotificationSink>>, events: UnboundedSender<DeliveryEvent>) {
+    let msg = AlertMessage {
+        title: "bags: test notification".to_string(),
+        body: "If you can read this, alerts will reach you here.".to_string(),
+        coin_id: "bitcoin".to_string(),
+        coin_name: "Bitcoin".to_string(),
+        coin_symbol: "btc".to_string(),
+        kind: AlertKind::Price,
+        target: 0.0,
+        current: 0.0,
+        direction: AlertDirection::Above,
+        digest: false,
+        price_display: String::new(),
+        target_display: String::new(),
+        ntfy: AlertNtfy::default(),
+    };
+    tokio::spawn(async move {
+        let mut results = Vec::new();
+        for sink in sinks {
+            let outcome = sink.send(&msg).await.err().map(|e| format!("{:#}", e));
+            results.push((sink.name().to_string(), outcome));
+        }
+        let _ = events.send(DeliveryEvent::TestFinished(results));
+    });
+}
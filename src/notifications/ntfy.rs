@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+
+use super::{error_excerpt, AlertMessage, NotificationSink, SendFuture};
+use crate::db::Db;
+
+pub const DEFAULT_NTFY_SERVER: &str = "https://ntfy.sh";
+
+/// ntfy priority names, lowest to highest.
+pub const NTFY_PRIORITIES: &[&str] = &["min", "low", "default", "high", "urgent"];
+
+#[derive(Debug, Clone, Default)]
+pub struct NtfyConfig {
+    /// Base URL of the ntfy server; empty means `DEFAULT_NTFY_SERVER`
+    pub server: String,
+    pub topic: String,
+    /// Access token, sent as a bearer token; takes precedence over basic auth
+    pub token: String,
+    pub username: String,
+    pub password: String,
+    pub priority: String,
+    /// Comma separated tags/emoji shortcodes
+    pub tags: String,
+    /// Click action URL; `{coin_id}` is replaced with the alert's coin
+    pub click_url: String,
+}
+
+impl NtfyConfig {
+    pub fn load(db: &Db) -> Self {
+        Self {
+            server: db.get_setting("ntfy_server").unwrap_or_default(),
+            topic: db.get_setting("ntfy_topic").unwrap_or_default(),
+            token: db.get_setting("ntfy_token").unwrap_or_default(),
+            username: db.get_setting("ntfy_username").unwrap_or_default(),
+            password: db.get_setting("ntfy_password").unwrap_or_default(),
+            priority: db
+                .get_setting("ntfy_priority")
+                .unwrap_or_else(|| "default".to_string()),
+            tags: db.get_setting("ntfy_tags").unwrap_or_default(),
+            click_url: db.get_setting("ntfy_click_url").unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self, db: &Db) -> Result<()> {
+        db.set_setting("ntfy_server", &self.server)?;
+        db.set_setting("ntfy_topic", &self.topic)?;
+        db.set_setting("ntfy_token", &self.token)?;
+        db.set_setting("ntfy_username", &self.username)?;
+        db.set_setting("ntfy_password", &self.password)?;
+        db.set_setting("ntfy_priority", &self.priority)?;
+        db.set_setting("ntfy_tags", &self.tags)?;
+        db.set_setting("ntfy_click_url", &self.click_url)?;
+        Ok(())
+    }
+
+    pub fn priority_idx(&self) -> usize {
+        NTFY_PRIORITIES
+            .iter()
+            .position(|p| *p == self.priority)
+            .unwrap_or(2)
+    }
+
+    fn topic_url(&self) -> String {
+        let server = if self.server.is_empty() {
+            DEFAULT_NTFY_SERVER
+        } else {
+            self.server.trim_end_matches('/')
+        };
+        format!("{}/{}", server, self.topic)
+    }
+}
+
+pub struct NtfySink {
+    client: reqwest::Client,
+    config: NtfyConfig,
+}
+
+impl NtfySink {
+    pub fn new(client: reqwest::Client, config: NtfyConfig) -> Self {
+        Self {
+            client,
+            config,
+        }
+    }
+}
+
+/// The alert's own header value, or the configured one when it has none.
+fn or_setting<'a>(own: &'a str, setting: &'a str) -> &'a str {
+    if own.is_empty() {
+        setting
+    } else {
+        own
+    }
+}
+
+impl NotificationSink for NtfySink {
+    fn name(&self) -> &'static str {
+        "ntfy"
+    }
+
+    fn send<'a>(&'a self, msg: &'a AlertMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            let c = &self.config;
+            let priority = or_setting(&msg.ntfy.priority, &c.priority);
+            let tags = or_setting(&msg.ntfy.tags, &c.tags);
+            let click_url = or_setting(&msg.ntfy.click_url, &c.click_url);
+            let mut req = self
+                .client
+                .post(c.topic_url())
+                .header("Title", &msg.title)
+                .header("Priority", priority);
+            if !tags.is_empty() {
+                req = req.header("Tags", tags);
+            }
+            if !click_url.is_empty() && !msg.digest {
+                req = req.header("Click", click_url.replace("{coin_id}", &msg.coin_id));
+            }
+            if !c.token.is_empty() {
+                req = req.bearer_auth(&c.token);
+            } else if !c.username.is_empty() {
+                req = req.basic_auth(&c.username, Some(&c.password));
+            }
+            let resp = req
+                .body(msg.body.clone())
+                .send()
+                .await
+                .context("Failed to reach ntfy")?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                anyhow::bail!("ntfy error {}: {}", status, error_excerpt(&body));
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex;
+
+use super::{AlertMessage, DeliveryEvent, NotificationSink};
+use crate::app::log_error;
+use crate::db::Db;
+
+/// Attempts before an entry is marked as permanently failed.
+const MAX_ATTEMPTS: u32 = 8;
+/// Delay after the first failure; doubles on each retry.
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 60 * 60;
+/// How long a claimed entry is hidden from other flushes while being sent.
+const LEASE_SECS: i64 = 120;
+
+/// Queue one delivery of `msg` per sink, first attempted at `not_before`.
+pub fn enqueue(db: &Db, sinks: &[Arc<dyn NotificationSink>], msg: &AlertMessage, not_before: i64) {
+    let payload = match serde_json::to_string(msg) {
+        Ok(p) => p,
+        Err(e) => {
+            log_error(&format!("Notification outbox: {}", e));
+            return;
+        }
+    };
+    let now = chrono::Utc::now().timestamp();
+    for sink in sinks {
+        if let Err(e) = db.enqueue_notification(sink.name(), &payload, now, not_before) {
+            log_error(&format!("Notification outbox: {}", e));
+        }
+    }
+}
+
+/// Seconds to wait after the `attempts`th failed attempt, or `None` once the
+/// entry is out of retries.
+fn retry_delay(attempts: u32) -> Option<i64> {
+    (attempts < MAX_ATTEMPTS)
+        .then(|| (BASE_BACKOFF_SECS << (attempts.max(1) - 1).min(16)).min(MAX_BACKOFF_SECS))
+}
+
+/// Attempt every due entry once. Failures are rescheduled with exponential
+/// backoff until `MAX_ATTEMPTS`, after which they are reported on `events`.
+pub async fn deliver_due(
+    db: &Arc<Mutex<Db>>,
+    sinks: &[Arc<dyn NotificationSink>],
+    events: &UnboundedSender<DeliveryEvent>,
+) {
+    let now = chrono::Utc::now().timestamp();
+    let due = {
+        let db = db.lock().await;
+        match db.claim_due_notifications(now, LEASE_SECS) {
+            Ok(due) => due,
+            Err(e) => {
+                log_error(&format!("Notification outbox: {}", e));
+                return;
+            }
+        }
+    };
+
+    for entry in due {
+        let attempts = entry.attempts + 1;
+        let result = match (
+            sinks.iter().find(|s| s.name() == entry.sink),
+            serde_json::from_str::<AlertMessage>(&entry.payload),
+        ) {
+            (Some(sink), Ok(msg)) => sink.send(&msg).await.map_err(|e| format!("{:#}", e)),
+            (None, _) => Err("sink is no longer configured".to_string()),
+            (_, Err(e)) => Err(format!("corrupt payload: {}", e)),
+        };
+
+        let retry = retry_delay(attempts).filter(|_| sinks.iter().any(|s| s.name() == entry.sink));
+        let db = db.lock().await;
+        let stored = match (result, retry) {
+            (Ok(()), _) => db.complete_notification(entry.id),
+            (Err(error), Some(delay)) => {
+                let next = chrono::Utc::now().timestamp() + delay;
+                db.reschedule_notification(entry.id, attempts, next, &error)
+            }
+            (Err(error), None) => {
+                log_error(&format!("Notification via {} failed: {}", entry.sink, error));
+                let _ = events.send(DeliveryEvent::Failed {
+                    sink: entry.sink.clone(),
+                    error: error.clone(),
+                });
+                db.fail_notification(entry.id, attempts, &error)
+            }
+        };
+        if let Err(e) = stored {
+            log_error(&format!("Notification outbox: {}", e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::SendFuture;
+    use tokio::sync::mpsc;
+
+    struct FailingSink;
+
+    impl NotificationSink for FailingSink {
+        fn name(&self) -> &'static str {
+            "webhook"
+        }
+
+        fn send<'a>(&'a self, _msg: &'a AlertMessage) -> SendFuture<'a> {
+            Box::pin(async { anyhow::bail!("connection refused") })
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_until_attempts_run_out() {
+        assert_eq!(retry_delay(1), Some(BASE_BACKOFF_SECS));
+        assert_eq!(retry_delay(2), Some(BASE_BACKOFF_SECS * 2));
+        assert_eq!(retry_delay(3), Some(BASE_BACKOFF_SECS * 4));
+        for attempts in 1..MAX_ATTEMPTS {
+            let delay = retry_delay(attempts).unwrap();
+            assert!(delay <= MAX_BACKOFF_SECS);
+        }
+        assert_eq!(retry_delay(MAX_ATTEMPTS), None);
+        assert_eq!(retry_delay(MAX_ATTEMPTS + 1), None);
+    }
+
+    #[tokio::test]
+    async fn entry_fails_after_max_attempts() {
+        let db = Arc::new(Mutex::new(Db::open_in_memory().unwrap()));
+        let sinks: Vec<Arc<dyn NotificationSink>> = vec![Arc::new(FailingSink)];
+        let (events, mut results) = mpsc::unbounded_channel();
+        let msg = AlertMessage::digest("title".to_string(), "body".to_string());
+        enqueue(&*db.lock().await, &sinks, &msg, 0);
+
+        for attempt in 1..MAX_ATTEMPTS {
+            deliver_due(&db, &sinks, &events).await;
+            assert!(results.try_recv().is_err(), "gave up after {} attempts", attempt);
+            // Skip the backoff so the next pass picks it up
+            let db = db.lock().await;
+            let entry = db.claim_due_notifications(i64::MAX / 2, 0).unwrap().pop().unwrap();
+            assert_eq!(entry.attempts, attempt);
+            db.reschedule_notification(entry.id, entry.attempts, 0, "").unwrap();
+        }
+
+        deliver_due(&db, &sinks, &events).await;
+        assert!(matches!(results.try_recv(), Ok(DeliveryEvent::Failed { sink, .. }) if sink == "webhook"));
+        assert!(db.lock().await.claim_due_notifications(i64::MAX / 2, 0).unwrap().is_empty());
+    }
+}
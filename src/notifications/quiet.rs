@@ -0,0 +1,128 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone};
+
+use crate::db::Db;
+
+/// What happens to notifications raised during quiet hours.
+pub const QUIET_MODES: &[&str] = &["hold", "drop"];
+
+/// A nightly window, in local time, during which only the terminal bell and
+/// row flash fire.
+#[derive(Debug, Clone, Default)]
+pub struct QuietHours {
+    /// "HH:MM"; quiet hours are off unless both ends parse
+    pub start: String,
+    pub end: String,
+    /// One of `QUIET_MODES`: hold until `end`, or drop outright
+    pub mode: String,
+}
+
+/// Outcome of checking a notification against quiet hours.
+#[derive(Debug, PartialEq)]
+pub enum QuietDecision {
+    Send,
+    /// Queue it, but don't attempt delivery before this timestamp
+    Hold(i64),
+    Drop,
+}
+
+impl QuietHours {
+    pub fn load(db: &Db) -> Self {
+        Self {
+            start: db.get_setting("quiet_start").unwrap_or_default(),
+            end: db.get_setting("quiet_end").unwrap_or_default(),
+            mode: db
+                .get_setting("quiet_mode")
+                .filter(|m| QUIET_MODES.contains(&m.as_str()))
+                .unwrap_or_else(|| QUIET_MODES[0].to_string()),
+        }
+    }
+
+    pub fn save(&self, db: &Db) -> Result<()> {
+        db.set_setting("quiet_start", &self.start)?;
+        db.set_setting("quiet_end", &self.end)?;
+        db.set_setting("quiet_mode", &self.mode)?;
+        Ok(())
+    }
+
+    pub fn mode_idx(&self) -> usize {
+        QUIET_MODES.iter().position(|m| *m == self.mode).unwrap_or(0)
+    }
+
+    fn bounds(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let parse = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M").ok();
+        let (start, end) = (parse(&self.start)?, parse(&self.end)?);
+        (start != end).then_some((start, end))
+    }
+
+    pub fn check(&self, now: DateTime<Local>) -> QuietDecision {
+        let Some((start, end)) = self.bounds() else {
+            return QuietDecision::Send;
+        };
+        let time = now.time();
+        // A window like 22:00-07:00 wraps past midnight
+        let quiet = if start < end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        };
+        if !quiet {
+            return QuietDecision::Send;
+        }
+        if self.mode == "drop" {
+            return QuietDecision::Drop;
+        }
+        let mut day = now.date_naive();
+        if time >= end {
+            day += Duration::days(1);
+        }
+        let release = Local
+            .from_local_datetime(&day.and_time(end))
+            .earliest()
+            .map(|t| t.timestamp())
+            // `end` falls in a DST gap; an hour later is past it
+            .unwrap_or(now.timestamp() + 3600);
+        QuietDecision::Hold(release)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hours(start: &str, end: &str, mode: &str) -> QuietHours {
+        QuietHours { start: start.to_string(), end: end.to_string(), mode: mode.to_string() }
+    }
+
+    fn at(day: u32, h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 1, day, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn overnight_window_holds_until_the_morning() {
+        let q = hours("22:00", "07:00", "hold");
+        // Before midnight the release is the next morning
+        assert_eq!(q.check(at(10, 23, 30)), QuietDecision::Hold(at(11, 7, 0).timestamp()));
+        assert_eq!(q.check(at(10, 22, 0)), QuietDecision::Hold(at(11, 7, 0).timestamp()));
+        // After midnight it's the same morning
+        assert_eq!(q.check(at(11, 3, 15)), QuietDecision::Hold(at(11, 7, 0).timestamp()));
+        assert_eq!(q.check(at(11, 7, 0)), QuietDecision::Send);
+        assert_eq!(q.check(at(11, 12, 0)), QuietDecision::Send);
+        assert_eq!(q.check(at(11, 21, 59)), QuietDecision::Send);
+    }
+
+    #[test]
+    fn daytime_window_does_not_wrap() {
+        let q = hours("09:00", "17:00", "hold");
+        assert_eq!(q.check(at(10, 8, 59)), QuietDecision::Send);
+        assert_eq!(q.check(at(10, 12, 0)), QuietDecision::Hold(at(10, 17, 0).timestamp()));
+        assert_eq!(q.check(at(10, 23, 0)), QuietDecision::Send);
+    }
+
+    #[test]
+    fn drop_mode_and_unset_windows() {
+        assert_eq!(hours("22:00", "07:00", "drop").check(at(11, 1, 0)), QuietDecision::Drop);
+        assert_eq!(hours("", "07:00", "hold").check(at(11, 1, 0)), QuietDecision::Send);
+        assert_eq!(hours("07:00", "07:00", "hold").check(at(11, 7, 0)), QuietDecision::Send);
+    }
+}
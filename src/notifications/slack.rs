@@ -0,0 +1,133 @@
+use anyhow::Context;
+use serde_json::json;
+
+use super::{coin_url, direction_arrow, error_excerpt, AlertMessage, NotificationSink, SendFuture};
+
+pub struct SlackSink {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl SlackSink {
+    pub fn new(client: reqwest::Client, webhook_url: &str) -> Self {
+        Self {
+            client,
+            webhook_url: webhook_url.to_string(),
+        }
+    }
+}
+
+impl NotificationSink for SlackSink {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    fn send<'a>(&'a self, msg: &'a AlertMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            if msg.digest {
+                let payload = json!({
+                    "text": msg.title,
+                    "blocks": [{
+                        "type": "section",
+                        "text": {
+                            "type": "mrkdwn",
+                            "text": format!("*{}*\n```{}```", mrkdwn_escape(&msg.title), mrkdwn_escape(&msg.body)),
+                        },
+                    }],
+                });
+                return self.post(&payload).await;
+            }
+            let heading = format!(
+                "{} <{}|{} ({})>",
+                direction_arrow(msg.direction),
+                coin_url(&msg.coin_id),
+                mrkdwn_escape(&msg.coin_name),
+                msg.coin_symbol.to_uppercase()
+            );
+            let payload = json!({
+                // Fallback for notifications and clients without block support
+                "text": msg.body,
+                "blocks": [
+                    {
+                        "type": "section",
+                        "text": {
+                            "type": "mrkdwn",
+                            "text": format!("*{}*\n{}", heading, mrkdwn_escape(&msg.body)),
+                        },
+                    },
+                    {
+                        "type": "context",
+                        "elements": [{
+                            "type": "mrkdwn",
+                            "text": format!(
+                                "Now *{}* \u{b7} Target *{}* \u{b7} {}",
+                                msg.current_text(),
+                                msg.target_text(),
+                                msg.kind.label()
+                            ),
+                        }],
+                    },
+                ],
+            });
+            self.post(&payload).await
+        })
+    }
+}
+
+impl SlackSink {
+    async fn post(&self, payload: &serde_json::Value) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .post(&self.webhook_url)
+            .json(payload)
+            .send()
+            .await
+            .context("Failed to reach Slack")?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Slack error {}: {}", status, error_excerpt(&body));
+        }
+        Ok(())
+    }
+}
+
+fn mrkdwn_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::test_support::{mock_http, sample_alert};
+
+    #[tokio::test]
+    async fn posts_blocks_to_webhook() {
+        let (base, server) = mock_http(200, "ok").await;
+        let url = format!("{}/services/T0/B0/xyz", base);
+        let mut msg = sample_alert();
+        msg.body = "a < b & c".to_string();
+        SlackSink::new(reqwest::Client::new(), &url).send(&msg).await.unwrap();
+
+        let req = server.await.unwrap();
+        assert_eq!(req.request_line, "POST /services/T0/B0/xyz");
+        let json = req.json();
+        assert_eq!(json["text"], "a < b & c");
+        let section = json["blocks"][0]["text"]["text"].as_str().unwrap();
+        assert!(section.contains("<https://www.coingecko.com/en/coins/bitcoin|Bitcoin (BTC)>"));
+        assert!(section.ends_with("a &lt; b &amp; c"));
+        let context = json["blocks"][1]["elements"][0]["text"].as_str().unwrap();
+        assert!(context.contains("Now *$70,123.45*"));
+    }
+
+    #[tokio::test]
+    async fn reports_error_body() {
+        let (base, server) = mock_http(403, "invalid_token").await;
+        let url = format!("{}/services/T0/B0/xyz", base);
+        let err = SlackSink::new(reqwest::Client::new(), &url).send(&sample_alert()).await.unwrap_err();
+        server.await.unwrap();
+        let err = format!("{:#}", err);
+        assert!(err.starts_with("Slack error 403"));
+        assert!(err.contains("invalid_token"));
+    }
+}
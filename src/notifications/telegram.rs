@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use serde_json::json;
+
+use super::{coin_url, direction_arrow, error_excerpt, html_escape, AlertMessage, NotificationSink, SendFuture};
+use crate::db::Db;
+
+pub const DEFAULT_TELEGRAM_API: &str = "https://api.telegram.org";
+
+#[derive(Debug, Clone, Default)]
+pub struct TelegramConfig {
+    /// Bot API base URL; empty means `DEFAULT_TELEGRAM_API`
+    pub api_url: String,
+    pub token: String,
+    pub chat_id: String,
+}
+
+impl TelegramConfig {
+    pub fn load(db: &Db) -> Self {
+        Self {
+            api_url: db.get_setting("telegram_api_url").unwrap_or_default(),
+            token: db.get_setting("telegram_token").unwrap_or_default(),
+            chat_id: db.get_setting("telegram_chat_id").unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self, db: &Db) -> Result<()> {
+        db.set_setting("telegram_api_url", &self.api_url)?;
+        db.set_setting("telegram_token", &self.token)?;
+        db.set_setting("telegram_chat_id", &self.chat_id)?;
+        Ok(())
+    }
+
+    pub fn is_configured(&self) -> bool {
+        !self.token.is_empty() && !self.chat_id.is_empty()
+    }
+}
+
+pub struct TelegramSink {
+    client: reqwest::Client,
+    config: TelegramConfig,
+}
+
+impl TelegramSink {
+    pub fn new(client: reqwest::Client, config: TelegramConfig) -> Self {
+        Self {
+            client,
+            config,
+        }
+    }
+}
+
+impl NotificationSink for TelegramSink {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn send<'a>(&'a self, msg: &'a AlertMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            let base = if self.config.api_url.is_empty() {
+                DEFAULT_TELEGRAM_API
+            } else {
+                self.config.api_url.trim_end_matches('/')
+            };
+            let url = format!("{}/bot{}/sendMessage", base, self.config.token);
+            let text = if msg.digest {
+                format!("<b>{}</b>\n<pre>{}</pre>", html_escape(&msg.title), html_escape(&msg.body))
+            } else {
+                format!(
+                "<b>{} {} ({})</b>\n{}\n\nTarget: <code>{}</code>  Now: <code>{}</code>\n<a href=\"{}\">View on CoinGecko</a>",
+                direction_arrow(msg.direction),
+                html_escape(&msg.coin_name),
+                html_escape(&msg.coin_symbol.to_uppercase()),
+                html_escape(&msg.body),
+                html_escape(&msg.target_text()),
+                html_escape(&msg.current_text()),
+                coin_url(&msg.coin_id),
+                )
+            };
+            let resp = self
+                .client
+                .post(&url)
+                .json(&json!({
+                    "chat_id": self.config.chat_id,
+                    "text": text,
+                    "parse_mode": "HTML",
+                    "disable_web_page_preview": true,
+                }))
+                .send()
+                .await
+                // The URL carries the bot token; keep it out of logs and the outbox
+                .map_err(reqwest::Error::without_url)
+                .context("Failed to reach Telegram")?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                anyhow::bail!("Telegram error {}: {}", status, error_excerpt(&body));
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::test_support::{mock_http, sample_alert};
+
+    fn sink(base: &str) -> TelegramSink {
+        TelegramSink::new(
+            reqwest::Client::new(),
+            TelegramConfig {
+                api_url: format!("{}/", base),
+                token: "123:secret".to_string(),
+                chat_id: "-42".to_string(),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn posts_html_message_to_bot_endpoint() {
+        let (base, server) = mock_http(200, r#"{"ok":true}"#).await;
+        let mut msg = sample_alert();
+        msg.coin_name = "<Bit&coin>".to_string();
+        sink(&base).send(&msg).await.unwrap();
+
+        let req = server.await.unwrap();
+        assert_eq!(req.request_line, "POST /bot123:secret/sendMessage");
+        let json = req.json();
+        assert_eq!(json["chat_id"], "-42");
+        assert_eq!(json["parse_mode"], "HTML");
+        let text = json["text"].as_str().unwrap();
+        assert!(text.contains("&lt;Bit&amp;coin&gt; (BTC)"));
+        assert!(text.contains("Target: <code>$70,000.00</code>"));
+    }
+
+    #[tokio::test]
+    async fn reports_error_body_without_the_token() {
+        let (base, server) = mock_http(400, r#"{"ok":false,"description":"Bad Request: chat not found"}"#).await;
+        let err = sink(&base).send(&sample_alert()).await.unwrap_err();
+        server.await.unwrap();
+        let err = format!("{:#}", err);
+        assert!(err.starts_with("Telegram error 400"));
+        assert!(err.contains("chat not found"));
+        assert!(!err.contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn connection_errors_do_not_leak_the_token() {
+        // Nothing listens on a port that was just released
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let err = sink(&format!("http://127.0.0.1:{}", port)).send(&sample_alert()).await.unwrap_err();
+        let err = format!("{:#}", err);
+        assert!(err.starts_with("Failed to reach Telegram"));
+        assert!(!err.contains("secret"));
+    }
+}
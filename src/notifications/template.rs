@@ -0,0 +1,82 @@
+use anyhow::Result;
+
+use crate::db::Db;
+
+/// Placeholders understood by the title and body templates.
+pub const TEMPLATE_PLACEHOLDERS: &str =
+    "{coin} {symbol} {kind} {price} {target} {direction} {distance} {time}";
+
+/// User overrides for the title and body of alert notifications. Blank
+/// fields fall back to the built-in wording for each alert kind.
+#[derive(Debug, Clone, Default)]
+pub struct MessageTemplates {
+    pub title: String,
+    pub body: String,
+}
+
+impl MessageTemplates {
+    pub fn load(db: &Db) -> Self {
+        Self {
+            title: db.get_setting("notification_title_template").unwrap_or_default(),
+            body: db.get_setting("notification_body_template").unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self, db: &Db) -> Result<()> {
+        db.set_setting("notification_title_template", &self.title)?;
+        db.set_setting("notification_body_template", &self.body)?;
+        Ok(())
+    }
+}
+
+/// Values substituted into a template.
+pub struct TemplateVars<'a> {
+    pub coin: &'a str,
+    pub symbol: &'a str,
+    pub kind: &'a str,
+    pub price: &'a str,
+    pub target: &'a str,
+    pub direction: &'a str,
+    pub distance: &'a str,
+    pub time: &'a str,
+}
+
+pub fn render(template: &str, vars: &TemplateVars) -> String {
+    super::fill_placeholders(template, |name| {
+        let value = match name {
+            "coin" => vars.coin,
+            "symbol" => vars.symbol,
+            "kind" => vars.kind,
+            "price" => vars.price,
+            "target" => vars.target,
+            "direction" => vars.direction,
+            "distance" => vars.distance,
+            "time" => vars.time,
+            _ => return None,
+        };
+        Some(value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coin_names_are_not_expanded_again() {
+        let vars = TemplateVars {
+            coin: "Weird {price} Coin",
+            symbol: "WRD",
+            kind: "Price",
+            price: "$1.00",
+            target: "$2.00",
+            direction: "above",
+            distance: "-50.00%",
+            time: "2026-01-10 09:00",
+        };
+        assert_eq!(
+            render("{coin} ({symbol}) at {price}, {distance} from {target} {other}", &vars),
+            "Weird {price} Coin (WRD) at $1.00, -50.00% from $2.00 {other}"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Fixtures for the sink tests: a sample alert and one-shot local servers
+//! standing in for the real services.
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use super::AlertMessage;
+use crate::types::{AlertDirection, AlertKind, AlertNtfy};
+
+pub fn sample_alert() -> AlertMessage {
+    AlertMessage {
+        title: "bags: Bitcoin alert".to_string(),
+        body: "Bitcoin hit above target $70,000.00 (now $70,123.45)".to_string(),
+        coin_id: "bitcoin".to_string(),
+        coin_name: "Bitcoin".to_string(),
+        coin_symbol: "btc".to_string(),
+        kind: AlertKind::Price,
+        target: 70000.0,
+        current: 70123.45,
+        direction: AlertDirection::Above,
+        digest: false,
+        price_display: "$70,123.45".to_string(),
+        target_display: "$70,000.00".to_string(),
+        ntfy: AlertNtfy::default(),
+    }
+}
+
+/// One HTTP request as the mock server saw it.
+pub struct Captured {
+    /// e.g. "POST /bot123:abc/sendMessage"
+    pub request_line: String,
+    pub body: String,
+}
+
+impl Captured {
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_str(&self.body).expect("request body is JSON")
+    }
+}
+
+/// Serve a single HTTP request with `status` and `response`. Returns the
+/// base URL and a handle resolving to the captured request.
+pub async fn mock_http(status: u16, response: &'static str) -> (String, JoinHandle<Captured>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).await.unwrap();
+        let mut len = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            if line.trim_end().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    len = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; len];
+        reader.read_exact(&mut body).await.unwrap();
+        let reply = format!(
+            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            response.len(),
+            response
+        );
+        reader.get_mut().write_all(reply.as_bytes()).await.unwrap();
+        let _ = reader.get_mut().shutdown().await;
+        Captured {
+            request_line: request_line.trim_end().rsplit_once(' ').unwrap().0.to_string(),
+            body: String::from_utf8(body).unwrap(),
+        }
+    });
+    (base, handle)
+}
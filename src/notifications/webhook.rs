@@ -0,0 +1,187 @@
+use anyhow::{Context, Result};
+use reqwest::Method;
+
+use super::{direction_str, error_excerpt, fill_placeholders, AlertMessage, NotificationSink, SendFuture};
+use crate::db::Db;
+
+pub const WEBHOOK_METHODS: &[&str] = &["POST", "PUT", "PATCH"];
+
+/// Placeholders: {coin}, {coin_id}, {symbol}, {kind}, {target}, {price},
+/// {direction}, {title}, {message}. String values are JSON-escaped.
+pub const DEFAULT_WEBHOOK_TEMPLATE: &str = r#"{"coin":"{coin}","coin_id":"{coin_id}","symbol":"{symbol}","kind":"{kind}","target":{target},"price":{price},"direction":"{direction}","message":"{message}"}"#;
+
+#[derive(Debug, Clone, Default)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub method: String,
+    /// `Name: value` pairs separated by `|`
+    pub headers: String,
+    /// JSON body; empty means `DEFAULT_WEBHOOK_TEMPLATE`
+    pub body_template: String,
+}
+
+impl WebhookConfig {
+    pub fn load(db: &Db) -> Self {
+        Self {
+            url: db.get_setting("webhook_url").unwrap_or_default(),
+            method: db
+                .get_setting("webhook_method")
+                .unwrap_or_else(|| WEBHOOK_METHODS[0].to_string()),
+            headers: db.get_setting("webhook_headers").unwrap_or_default(),
+            body_template: db.get_setting("webhook_template").unwrap_or_default(),
+        }
+    }
+
+    pub fn save(&self, db: &Db) -> Result<()> {
+        db.set_setting("webhook_url", &self.url)?;
+        db.set_setting("webhook_method", &self.method)?;
+        db.set_setting("webhook_headers", &self.headers)?;
+        db.set_setting("webhook_template", &self.body_template)?;
+        Ok(())
+    }
+
+    pub fn method_idx(&self) -> usize {
+        WEBHOOK_METHODS
+            .iter()
+            .position(|m| m.eq_ignore_ascii_case(&self.method))
+            .unwrap_or(0)
+    }
+
+    fn parsed_headers(&self) -> Vec<(String, String)> {
+        self.headers
+            .split('|')
+            .filter_map(|h| {
+                let (name, value) = h.split_once(':')?;
+                let name = name.trim();
+                if name.is_empty() {
+                    return None;
+                }
+                Some((name.to_string(), value.trim().to_string()))
+            })
+            .collect()
+    }
+}
+
+pub struct WebhookSink {
+    client: reqwest::Client,
+    config: WebhookConfig,
+}
+
+impl WebhookSink {
+    pub fn new(client: reqwest::Client, config: WebhookConfig) -> Self {
+        Self {
+            client,
+            config,
+        }
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn send<'a>(&'a self, msg: &'a AlertMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            let method = Method::from_bytes(self.config.method.to_uppercase().as_bytes())
+                .unwrap_or(Method::POST);
+            let template = if self.config.body_template.is_empty() {
+                DEFAULT_WEBHOOK_TEMPLATE
+            } else {
+                &self.config.body_template
+            };
+            let mut req = self
+                .client
+                .request(method, &self.config.url)
+                .header("Content-Type", "application/json");
+            for (name, value) in self.config.parsed_headers() {
+                req = req.header(name, value);
+            }
+            let resp = req
+                .body(render_template(template, msg))
+                .send()
+                .await
+                .context("Failed to reach webhook")?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                anyhow::bail!("Webhook error {}: {}", status, error_excerpt(&body));
+            }
+            Ok(())
+        })
+    }
+}
+
+fn render_template(template: &str, msg: &AlertMessage) -> String {
+    fill_placeholders(template, |name| {
+        Some(match name {
+            "coin_id" => json_escape(&msg.coin_id),
+            "coin" => json_escape(&msg.coin_name),
+            "symbol" => json_escape(&msg.coin_symbol.to_uppercase()),
+            "kind" => msg.kind_str().to_string(),
+            "target" => json_number(msg.target),
+            "price" => json_number(msg.current),
+            "direction" => direction_str(msg.direction).to_string(),
+            "title" => json_escape(&msg.title),
+            "message" => json_escape(&msg.body),
+            _ => return None,
+        })
+    })
+}
+
+/// A JSON number, or `null` for NaN and infinities.
+fn json_number(v: f64) -> String {
+    serde_json::to_string(&v).unwrap_or_else(|_| "null".to_string())
+}
+
+/// Escape a string for use inside an existing pair of JSON quotes.
+fn json_escape(s: &str) -> String {
+    let quoted = serde_json::to_string(s).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::test_support::sample_alert;
+
+    #[test]
+    fn default_template_is_valid_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_template(DEFAULT_WEBHOOK_TEMPLATE, &sample_alert())).unwrap();
+        assert_eq!(json["coin"], "Bitcoin");
+        assert_eq!(json["symbol"], "BTC");
+        assert_eq!(json["target"], 70000.0);
+        assert_eq!(json["price"], 70123.45);
+        assert_eq!(json["direction"], "above");
+    }
+
+    #[test]
+    fn placeholders_inside_values_are_not_expanded() {
+        let mut msg = sample_alert();
+        msg.coin_name = "Coin {price} \"quoted\"".to_string();
+        msg.body = "{target} {coin_id} {unknown}".to_string();
+        let json: serde_json::Value =
+            serde_json::from_str(&render_template(DEFAULT_WEBHOOK_TEMPLATE, &msg)).unwrap();
+        assert_eq!(json["coin"], "Coin {price} \"quoted\"");
+        assert_eq!(json["message"], "{target} {coin_id} {unknown}");
+    }
+
+    #[test]
+    fn non_finite_numbers_become_null() {
+        let mut msg = sample_alert();
+        msg.current = f64::NAN;
+        msg.target = f64::INFINITY;
+        let json: serde_json::Value =
+            serde_json::from_str(&render_template(DEFAULT_WEBHOOK_TEMPLATE, &msg)).unwrap();
+        assert!(json["price"].is_null());
+        assert!(json["target"].is_null());
+    }
+
+    #[test]
+    fn unknown_and_unclosed_braces_are_kept() {
+        let msg = sample_alert();
+        assert_eq!(render_template("{x} {coin} {", &msg), "{x} Bitcoin {");
+        assert_eq!(render_template("{{symbol}}", &msg), "{BTC}");
+    }
+}
@@ -25,6 +25,79 @@ pub struct Coin {
     pub low_24h: Option<f64>,
     pub circulating_supply: Option<f64>,
     pub max_supply: Option<f64>,
+    #[serde(default)]
+    pub total_supply: Option<f64>,
+    #[serde(default)]
+    pub fully_diluted_valuation: Option<f64>,
+    #[serde(default)]
+    pub ath: Option<f64>,
+    /// RFC 3339
+    #[serde(default)]
+    pub ath_date: Option<String>,
+    #[serde(default)]
+    pub atl: Option<f64>,
+    #[serde(default)]
+    pub atl_date: Option<String>,
+}
+
+/// Slow-changing profile from `/coins/{id}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoinDetails {
+    pub categories: Vec<String>,
+    /// Plain text, HTML links stripped
+    pub description: String,
+    pub homepage: Option<String>,
+    pub explorers: Vec<String>,
+}
+
+/// A sector from `/coins/categories`; totals are in USD.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketCategory {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub market_cap: Option<f64>,
+    #[serde(default)]
+    pub market_cap_change_24h: Option<f64>,
+    #[serde(default)]
+    pub volume_24h: Option<f64>,
+    #[serde(default)]
+    pub top_3_coins_id: Option<Vec<String>>,
+}
+
+/// Ordering of the sector overview, always largest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectorSort {
+    MarketCap,
+    Change24h,
+    Volume,
+}
+
+impl SectorSort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SectorSort::MarketCap => "mcap",
+            SectorSort::Change24h => "24h%",
+            SectorSort::Volume => "volume",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SectorSort::MarketCap => SectorSort::Change24h,
+            SectorSort::Change24h => SectorSort::Volume,
+            SectorSort::Volume => SectorSort::MarketCap,
+        }
+    }
+
+    pub fn key(&self, c: &MarketCategory) -> f64 {
+        let v = match self {
+            SectorSort::MarketCap => c.market_cap,
+            SectorSort::Change24h => c.market_cap_change_24h,
+            SectorSort::Volume => c.volume_24h,
+        };
+        v.unwrap_or(f64::NEG_INFINITY)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -34,7 +107,7 @@ pub struct Holding {
     pub buy_price: Option<f64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalMarketStats {
     pub total_market_cap_usd: f64,
     pub btc_dominance: f64,
@@ -44,18 +117,139 @@ pub struct GlobalMarketStats {
 
 #[derive(Debug, Clone)]
 pub struct PriceAlert {
+    pub id: i64,
     pub coin_id: String,
+    pub kind: AlertKind,
+    /// Price target, volume multiplier or rank bracket depending on `kind`
     pub target_price: f64,
     pub direction: AlertDirection,
     pub triggered: bool,
+    pub created_at: Option<i64>,
+    pub triggered_at: Option<i64>,
+    pub trigger_price: Option<f64>,
+    /// Sinks this alert notifies; empty means the globally enabled ones
+    pub sinks: Vec<String>,
+    pub ntfy: AlertNtfy,
 }
 
+/// Per-alert ntfy headers; empty fields fall back to the ntfy settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertNtfy {
+    pub priority: String,
+    /// Comma separated tags/emoji shortcodes
+    pub tags: String,
+    /// Click action URL; `{coin_id}` is replaced with the alert's coin
+    pub click_url: String,
+}
+
+/// Rows of the alert popup, moved between with Up/Down.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertField {
+    Target,
+    Sinks,
+    NtfyPriority,
+    NtfyTags,
+    NtfyClickUrl,
+}
+
+impl AlertField {
+    pub const ALL: &'static [AlertField] = &[
+        AlertField::Target,
+        AlertField::Sinks,
+        AlertField::NtfyPriority,
+        AlertField::NtfyTags,
+        AlertField::NtfyClickUrl,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AlertDirection {
     Above,
     Below,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertKind {
+    #[serde(rename = "price")]
+    Price,
+    #[serde(rename = "volume")]
+    VolumeSpike,
+    #[serde(rename = "rank")]
+    Rank,
+}
+
+impl AlertKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            AlertKind::Price => "Price",
+            AlertKind::VolumeSpike => "Volume spike",
+            AlertKind::Rank => "Rank bracket",
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AlertKind::Price => "price",
+            AlertKind::VolumeSpike => "volume",
+            AlertKind::Rank => "rank",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "volume" => AlertKind::VolumeSpike,
+            "rank" => AlertKind::Rank,
+            _ => AlertKind::Price,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            AlertKind::Price => AlertKind::VolumeSpike,
+            AlertKind::VolumeSpike => AlertKind::Rank,
+            AlertKind::Rank => AlertKind::Price,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            AlertKind::Price => AlertKind::Rank,
+            AlertKind::VolumeSpike => AlertKind::Price,
+            AlertKind::Rank => AlertKind::VolumeSpike,
+        }
+    }
+}
+
+/// One refresh worth of volume/rank data for a coin, used to evaluate
+/// volume spike and rank bracket alerts.
+#[derive(Debug, Clone)]
+pub struct MarketSample {
+    pub recorded_at: i64,
+    pub total_volume: f64,
+    pub market_cap_rank: Option<u32>,
+}
+
+/// A queued notification delivery for one sink.
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    pub id: i64,
+    pub sink: String,
+    /// JSON-encoded `AlertMessage`
+    pub payload: String,
+    pub attempts: u32,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SortColumn {
     Rank,
@@ -74,19 +268,12 @@ pub enum SortDirection {
     Desc,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum NotificationMethod {
-    None,
-    Desktop,
-    Ntfy,
-    Both,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Tab {
     Markets,
     Favourites,
     Portfolio,
+    Discover,
 }
 
 impl Tab {
@@ -95,6 +282,7 @@ impl Tab {
             Tab::Markets => 0,
             Tab::Favourites => 1,
             Tab::Portfolio => 2,
+            Tab::Discover => 3,
         }
     }
 
@@ -103,6 +291,7 @@ impl Tab {
             0 => Tab::Markets,
             1 => Tab::Favourites,
             2 => Tab::Portfolio,
+            3 => Tab::Discover,
             _ => Tab::Markets,
         }
     }
@@ -112,11 +301,47 @@ impl Tab {
             Tab::Markets => "Markets",
             Tab::Favourites => "Favourites",
             Tab::Portfolio => "Portfolio",
+            Tab::Discover => "Discover",
         }
     }
 
     pub fn next(self) -> Self {
-        Self::from_index((self.index() + 1) % 3)
+        Self::from_index((self.index() + 1) % 4)
+    }
+}
+
+/// Price change window ranking the Discover tab's gainers and losers and
+/// colouring the treemap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeWindow {
+    Hour1,
+    Day1,
+    Day7,
+}
+
+impl ChangeWindow {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChangeWindow::Hour1 => "1h",
+            ChangeWindow::Day1 => "24h",
+            ChangeWindow::Day7 => "7d",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ChangeWindow::Hour1 => ChangeWindow::Day1,
+            ChangeWindow::Day1 => ChangeWindow::Day7,
+            ChangeWindow::Day7 => ChangeWindow::Hour1,
+        }
+    }
+
+    pub fn change(&self, coin: &Coin) -> Option<f64> {
+        match self {
+            ChangeWindow::Hour1 => coin.price_change_percentage_1h_in_currency,
+            ChangeWindow::Day1 => coin.price_change_percentage_24h_in_currency,
+            ChangeWindow::Day7 => coin.price_change_percentage_7d_in_currency,
+        }
     }
 }
 
@@ -125,45 +350,205 @@ pub enum ChartView {
     Day1,
     Day7,
     Day30,
+    Day90,
+    Day180,
+    Year1,
+    Max,
 }
 
 impl ChartView {
+    const ALL: [ChartView; 7] = [
+        ChartView::Day1,
+        ChartView::Day7,
+        ChartView::Day30,
+        ChartView::Day90,
+        ChartView::Day180,
+        ChartView::Year1,
+        ChartView::Max,
+    ];
+
     pub fn label(self) -> &'static str {
         match self {
             ChartView::Day1 => "1D",
             ChartView::Day7 => "7D",
             ChartView::Day30 => "30D",
+            ChartView::Day90 => "90D",
+            ChartView::Day180 => "180D",
+            ChartView::Year1 => "1Y",
+            ChartView::Max => "Max",
         }
     }
 
+    /// Length of the range in days; 0 for `Max`, which has no fixed start
     pub fn days(self) -> u32 {
         match self {
             ChartView::Day1 => 1,
             ChartView::Day7 => 7,
             ChartView::Day30 => 30,
+            ChartView::Day90 => 90,
+            ChartView::Day180 => 180,
+            ChartView::Year1 => 365,
+            ChartView::Max => 0,
         }
     }
 
-    pub fn next(self) -> Self {
+    /// The `days` parameter for CoinGecko's chart and OHLC endpoints
+    pub fn api_days(self) -> String {
         match self {
-            ChartView::Day1 => ChartView::Day7,
-            ChartView::Day7 => ChartView::Day30,
-            ChartView::Day30 => ChartView::Day1,
+            ChartView::Max => "max".to_string(),
+            _ => self.days().to_string(),
         }
     }
 
-    pub fn prev(self) -> Self {
+    /// Oldest timestamp that belongs in this range
+    pub fn window_start(self, now: i64) -> i64 {
+        match self {
+            ChartView::Max => i64::MIN,
+            _ => now - self.days() as i64 * 86400,
+        }
+    }
+
+    /// Spacing of CoinGecko's auto-granularity data for this range
+    pub fn step_secs(self) -> i64 {
         match self {
-            ChartView::Day1 => ChartView::Day30,
-            ChartView::Day7 => ChartView::Day1,
-            ChartView::Day30 => ChartView::Day7,
+            ChartView::Day1 => 5 * 60,
+            ChartView::Day7 | ChartView::Day30 | ChartView::Day90 => 60 * 60,
+            _ => 24 * 60 * 60,
         }
     }
+
+    /// Ranges long enough that a log price axis usually reads better
+    pub fn is_long(self) -> bool {
+        matches!(self, ChartView::Year1 | ChartView::Max)
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let i = Self::ALL.iter().position(|v| *v == self).unwrap_or(0);
+        Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// One `market_chart` sample.
+#[derive(Debug, Clone, Copy)]
+pub struct PricePoint {
+    /// Unix seconds
+    pub ts: i64,
+    pub price: f64,
+    /// Rolling 24h volume at `ts`
+    pub volume: f64,
 }
 
 #[derive(Debug, Clone)]
 pub struct PriceHistory {
+    /// Unix seconds, parallel to `prices` and `volumes`
+    pub timestamps: Vec<i64>,
     pub prices: Vec<f64>,
+    pub volumes: Vec<f64>,
+}
+
+impl PriceHistory {
+    pub fn from_points(points: &[PricePoint]) -> Self {
+        Self {
+            timestamps: points.iter().map(|p| p.ts).collect(),
+            prices: points.iter().map(|p| p.price).collect(),
+            volumes: points.iter().map(|p| p.volume).collect(),
+        }
+    }
+
+    /// Index of the point closest in time to `ts`.
+    pub fn nearest(&self, ts: i64) -> Option<usize> {
+        if self.timestamps.is_empty() {
+            return None;
+        }
+        let i = self.timestamps.partition_point(|t| *t < ts);
+        if i == 0 {
+            return Some(0);
+        }
+        if i == self.timestamps.len() {
+            return Some(i - 1);
+        }
+        if ts - self.timestamps[i - 1] <= self.timestamps[i] - ts {
+            Some(i - 1)
+        } else {
+            Some(i)
+        }
+    }
+}
+
+/// One OHLC candle from `/coins/{id}/ohlc`; `ts` is the candle's close time.
+#[derive(Debug, Clone, Copy)]
+pub struct Candle {
+    pub ts: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartMode {
+    Line,
+    Candles,
+}
+
+impl ChartMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            ChartMode::Line => "Line",
+            ChartMode::Candles => "Candles",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            ChartMode::Line => ChartMode::Candles,
+            ChartMode::Candles => ChartMode::Line,
+        }
+    }
+}
+
+/// Moving-average overlay on the coin chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovingAverage {
+    Off,
+    Sma,
+    Ema,
+}
+
+impl MovingAverage {
+    /// Window lengths drawn, in chart samples
+    pub const PERIODS: [usize; 3] = [20, 50, 200];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MovingAverage::Off => "",
+            MovingAverage::Sma => "SMA",
+            MovingAverage::Ema => "EMA",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            MovingAverage::Off => MovingAverage::Sma,
+            MovingAverage::Sma => MovingAverage::Ema,
+            MovingAverage::Ema => MovingAverage::Off,
+        }
+    }
+}
+
+/// Indicators shown in the coin detail popup.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChartIndicators {
+    pub bollinger: bool,
+    pub rsi: bool,
+    pub macd: bool,
+    /// Realised volatility and max drawdown
+    pub risk: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -186,85 +571,265 @@ pub enum InputMode {
     Filtering,
     EditingAlert,
     EditingBuyPrice,
+    Sectors,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SettingsField {
     Currency,
     Theme,
+    MarketsLimit,
+    LazyLoad,
     CoingeckoApiKey,
     CoinmarketcapApiKey,
     Notifications,
+    NtfyServer,
     NtfyTopic,
+    NtfyToken,
+    NtfyUsername,
+    NtfyPassword,
+    NtfyPriority,
+    NtfyTags,
+    NtfyClickUrl,
+    WebhookUrl,
+    WebhookMethod,
+    WebhookHeaders,
+    WebhookTemplate,
+    SmtpHost,
+    SmtpPort,
+    SmtpSecurity,
+    SmtpUsername,
+    SmtpPassword,
+    SmtpFrom,
+    SmtpTo,
+    TelegramApiUrl,
+    TelegramToken,
+    TelegramChatId,
+    DiscordWebhookUrl,
+    SlackWebhookUrl,
+    ExecCommand,
+    ExecTimeout,
+    QuietStart,
+    QuietEnd,
+    QuietMode,
+    TemplateTitle,
+    TemplateBody,
+    TestNotification,
 }
 
 impl SettingsField {
+    /// Display order in the settings dialog
+    pub const ALL: &'static [SettingsField] = &[
+        SettingsField::Currency,
+        SettingsField::Theme,
+        SettingsField::MarketsLimit,
+        SettingsField::LazyLoad,
+        SettingsField::CoingeckoApiKey,
+        SettingsField::CoinmarketcapApiKey,
+        SettingsField::Notifications,
+        SettingsField::QuietStart,
+        SettingsField::QuietEnd,
+        SettingsField::QuietMode,
+        SettingsField::TemplateTitle,
+        SettingsField::TemplateBody,
+        SettingsField::NtfyServer,
+        SettingsField::NtfyTopic,
+        SettingsField::NtfyToken,
+        SettingsField::NtfyUsername,
+        SettingsField::NtfyPassword,
+        SettingsField::NtfyPriority,
+        SettingsField::NtfyTags,
+        SettingsField::NtfyClickUrl,
+        SettingsField::WebhookUrl,
+        SettingsField::WebhookMethod,
+        SettingsField::WebhookHeaders,
+        SettingsField::WebhookTemplate,
+        SettingsField::SmtpHost,
+        SettingsField::SmtpPort,
+        SettingsField::SmtpSecurity,
+        SettingsField::SmtpUsername,
+        SettingsField::SmtpPassword,
+        SettingsField::SmtpFrom,
+        SettingsField::SmtpTo,
+        SettingsField::TelegramApiUrl,
+        SettingsField::TelegramToken,
+        SettingsField::TelegramChatId,
+        SettingsField::DiscordWebhookUrl,
+        SettingsField::SlackWebhookUrl,
+        SettingsField::ExecCommand,
+        SettingsField::ExecTimeout,
+        SettingsField::TestNotification,
+    ];
+
     pub fn label(self) -> &'static str {
         match self {
             SettingsField::Currency => "Currency",
             SettingsField::Theme => "Theme",
+            SettingsField::MarketsLimit => "Markets Size",
+            SettingsField::LazyLoad => "Load Pages On Scroll",
             SettingsField::CoingeckoApiKey => "CoinGecko API Key",
             SettingsField::CoinmarketcapApiKey => "CoinMarketCap API Key",
             SettingsField::Notifications => "Notifications",
+            SettingsField::NtfyServer => "Ntfy Server (blank for ntfy.sh)",
             SettingsField::NtfyTopic => "Ntfy Topic",
+            SettingsField::NtfyToken => "Ntfy Access Token",
+            SettingsField::NtfyUsername => "Ntfy Username",
+            SettingsField::NtfyPassword => "Ntfy Password",
+            SettingsField::NtfyPriority => "Ntfy Default Priority",
+            SettingsField::NtfyTags => "Ntfy Default Tags (comma separated)",
+            SettingsField::NtfyClickUrl => "Ntfy Default Click URL ({coin_id} placeholder)",
+            SettingsField::WebhookUrl => "Webhook URL",
+            SettingsField::WebhookMethod => "Webhook Method",
+            SettingsField::WebhookHeaders => "Webhook Headers (Name: value | ...)",
+            SettingsField::WebhookTemplate => "Webhook Body Template (JSON)",
+            SettingsField::SmtpHost => "SMTP Host",
+            SettingsField::SmtpPort => "SMTP Port (blank for default)",
+            SettingsField::SmtpSecurity => "SMTP Security",
+            SettingsField::SmtpUsername => "SMTP Username",
+            SettingsField::SmtpPassword => "SMTP Password",
+            SettingsField::SmtpFrom => "Email From",
+            SettingsField::SmtpTo => "Email To (comma separated)",
+            SettingsField::TelegramApiUrl => "Telegram API URL (blank for api.telegram.org)",
+            SettingsField::TelegramToken => "Telegram Bot Token",
+            SettingsField::TelegramChatId => "Telegram Chat ID",
+            SettingsField::DiscordWebhookUrl => "Discord Webhook URL",
+            SettingsField::SlackWebhookUrl => "Slack Webhook URL",
+            SettingsField::ExecCommand => "Exec Command (BAGS_* env + JSON stdin)",
+            SettingsField::ExecTimeout => "Exec Timeout Seconds (blank for 10)",
+            SettingsField::QuietStart => "Quiet Hours Start (HH:MM, blank for off)",
+            SettingsField::QuietEnd => "Quiet Hours End (HH:MM)",
+            SettingsField::QuietMode => "During Quiet Hours",
+            SettingsField::TemplateTitle => "Alert Title Template (blank for default)",
+            SettingsField::TemplateBody => "Alert Body Template (blank for default)",
+            SettingsField::TestNotification => "Test Notification",
         }
     }
 
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|f| *f == self).unwrap_or(0)
+    }
+
     pub fn next(self) -> Self {
-        match self {
-            SettingsField::Currency => SettingsField::Theme,
-            SettingsField::Theme => SettingsField::CoingeckoApiKey,
-            SettingsField::CoingeckoApiKey => SettingsField::CoinmarketcapApiKey,
-            SettingsField::CoinmarketcapApiKey => SettingsField::Notifications,
-            SettingsField::Notifications => SettingsField::NtfyTopic,
-            SettingsField::NtfyTopic => SettingsField::Currency,
-        }
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
     }
 
     pub fn prev(self) -> Self {
-        match self {
-            SettingsField::Currency => SettingsField::NtfyTopic,
-            SettingsField::Theme => SettingsField::Currency,
-            SettingsField::CoingeckoApiKey => SettingsField::Theme,
-            SettingsField::CoinmarketcapApiKey => SettingsField::CoingeckoApiKey,
-            SettingsField::Notifications => SettingsField::CoinmarketcapApiKey,
-            SettingsField::NtfyTopic => SettingsField::Notifications,
-        }
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
     }
 
     pub fn is_text_field(self) -> bool {
-        matches!(self, SettingsField::CoingeckoApiKey | SettingsField::CoinmarketcapApiKey | SettingsField::NtfyTopic)
+        matches!(
+            self,
+            SettingsField::CoingeckoApiKey
+                | SettingsField::CoinmarketcapApiKey
+                | SettingsField::NtfyServer
+                | SettingsField::NtfyTopic
+                | SettingsField::NtfyToken
+                | SettingsField::NtfyUsername
+                | SettingsField::NtfyPassword
+                | SettingsField::NtfyTags
+                | SettingsField::NtfyClickUrl
+                | SettingsField::WebhookUrl
+                | SettingsField::WebhookHeaders
+                | SettingsField::WebhookTemplate
+                | SettingsField::SmtpHost
+                | SettingsField::SmtpPort
+                | SettingsField::SmtpUsername
+                | SettingsField::SmtpPassword
+                | SettingsField::SmtpFrom
+                | SettingsField::SmtpTo
+                | SettingsField::TelegramApiUrl
+                | SettingsField::TelegramToken
+                | SettingsField::TelegramChatId
+                | SettingsField::DiscordWebhookUrl
+                | SettingsField::SlackWebhookUrl
+                | SettingsField::ExecCommand
+                | SettingsField::ExecTimeout
+                | SettingsField::QuietStart
+                | SettingsField::QuietEnd
+                | SettingsField::TemplateTitle
+                | SettingsField::TemplateBody
+        )
+    }
+
+    /// Text fields whose value is a notification template with placeholders
+    pub fn is_template_field(self) -> bool {
+        matches!(self, SettingsField::TemplateTitle | SettingsField::TemplateBody)
     }
 
     pub fn is_cycle_field(self) -> bool {
-        matches!(self, SettingsField::Currency | SettingsField::Theme | SettingsField::Notifications)
+        matches!(
+            self,
+            SettingsField::Currency
+                | SettingsField::Theme
+                | SettingsField::MarketsLimit
+                | SettingsField::LazyLoad
+                | SettingsField::Notifications
+                | SettingsField::NtfyPriority
+                | SettingsField::WebhookMethod
+                | SettingsField::SmtpSecurity
+                | SettingsField::QuietMode
+        )
+    }
+
+    /// Whether the value is a secret that should be masked when not editing
+    pub fn is_masked(self) -> bool {
+        matches!(
+            self,
+            SettingsField::CoingeckoApiKey
+                | SettingsField::CoinmarketcapApiKey
+                | SettingsField::NtfyToken
+                | SettingsField::NtfyPassword
+                | SettingsField::WebhookHeaders
+                | SettingsField::SmtpPassword
+                | SettingsField::TelegramToken
+                | SettingsField::DiscordWebhookUrl
+                | SettingsField::SlackWebhookUrl
+        )
     }
 }
 
-pub const CURRENCIES: &[&str] = &[
-    "usd", "eur", "gbp", "jpy", "aud", "cad", "chf", "cny", "krw", "inr", "brl", "btc", "eth",
+/// Coins counted as stablecoins in the portfolio allocation, besides any
+/// whose cached profile lists the Stablecoins category.
+pub const STABLECOIN_IDS: &[&str] = &[
+    "tether", "usd-coin", "dai", "first-digital-usd", "ethena-usde", "usds", "true-usd",
+    "paypal-usd", "frax", "binance-usd", "paxos-standard", "gemini-dollar", "liquity-usd",
+    "euro-coin", "stasis-eurs", "tether-eurt",
 ];
 
-pub const NOTIFICATION_METHODS: &[&str] = &["none", "desktop", "ntfy", "both"];
+/// Coins ranked this high or better count as majors in the allocation.
+pub const MAJOR_RANK: u32 = 10;
 
-pub fn notification_method_from_str(s: &str) -> NotificationMethod {
-    match s {
-        "desktop" => NotificationMethod::Desktop,
-        "ntfy" => NotificationMethod::Ntfy,
-        "both" => NotificationMethod::Both,
-        _ => NotificationMethod::None,
-    }
+/// Broad buckets for the portfolio allocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetClass {
+    Stablecoin,
+    Major,
+    Alt,
 }
 
-pub fn notification_method_label(m: NotificationMethod) -> &'static str {
-    match m {
-        NotificationMethod::None => "none",
-        NotificationMethod::Desktop => "desktop",
-        NotificationMethod::Ntfy => "ntfy",
-        NotificationMethod::Both => "both",
+impl AssetClass {
+    pub const ALL: [AssetClass; 3] = [AssetClass::Stablecoin, AssetClass::Major, AssetClass::Alt];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AssetClass::Stablecoin => "Stablecoins",
+            AssetClass::Major => "Majors",
+            AssetClass::Alt => "Alts",
+        }
     }
 }
 
+/// Choices for how many coins the Markets tab lists.
+pub const MARKET_LIMITS: &[u32] = &[50, 100, 250, 500, 1000];
+
+pub const CURRENCIES: &[&str] = &[
+    "usd", "eur", "gbp", "jpy", "aud", "cad", "chf", "cny", "krw", "inr", "brl", "btc", "eth",
+];
+
+/// Notification sinks that can be enabled in settings, in display order.
+pub const NOTIFICATION_SINKS: &[&str] = &["desktop", "ntfy", "webhook", "email", "telegram", "discord", "slack", "exec"];
+
 pub fn currency_symbol(code: &str) -> &'static str {
     match code {
         "usd" => "$",
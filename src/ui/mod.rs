@@ -1,2 +1,3 @@
 mod render;
 pub use render::draw;
+pub(crate) use render::format_price;
@@ -1,14 +1,19 @@
+use std::collections::HashSet;
+
 use ratatui::{
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
+    symbols,
     text::{Line, Span},
     widgets::{
-        Block, Borders, Cell, Clear, Paragraph, Row, Sparkline, Table,
+        Axis, Block, Borders, Cell, Chart, Clear, Dataset, GraphType, Paragraph, Row, Sparkline, Table,
     },
     Frame,
 };
 
-use crate::app::App;
+use crate::app::{App, ALLOCATION_OTHER_PCT};
+use crate::indicators;
 use crate::theme::THEME_NAMES;
 use crate::types::*;
 
@@ -38,8 +43,10 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     draw_main(f, app, chunks[1]);
     draw_bottom_bar(f, app, chunks[2]);
 
-    if app.popup_open {
-        draw_popup(f, app);
+    app.chart_area = if app.popup_open { draw_popup(f, app) } else { None };
+
+    if app.compare_open {
+        draw_compare(f, app);
     }
 
     if app.input_mode == InputMode::EditingAmount {
@@ -61,6 +68,10 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     if app.input_mode == InputMode::SearchCoin || app.input_mode == InputMode::SearchResults {
         draw_search(f, app);
     }
+
+    if app.input_mode == InputMode::Sectors {
+        draw_sectors(f, app);
+    }
 }
 
 // -- Lock screen --
@@ -131,7 +142,7 @@ fn draw_lock_screen(f: &mut Frame, app: &App) {
 fn draw_top_bar(f: &mut Frame, app: &App, area: Rect) {
     let t = &app.theme;
 
-    let tabs_list = [Tab::Markets, Tab::Favourites, Tab::Portfolio];
+    let tabs_list = [Tab::Markets, Tab::Favourites, Tab::Portfolio, Tab::Discover];
     let mut spans: Vec<Span> = Vec::new();
 
     spans.push(Span::styled(
@@ -178,7 +189,14 @@ fn draw_top_bar(f: &mut Frame, app: &App, area: Rect) {
     }
 
     // Right-align refresh info
-    let refresh_info = if app.loading {
+    let stale = app.stale_since.map(stale_label);
+    let refresh_info = if let Some(ref stale) = stale {
+        if app.loading {
+            format!("{} \u{b7} loading...", stale)
+        } else {
+            stale.clone()
+        }
+    } else if app.loading {
         "loading...".to_string()
     } else if app.last_refresh_display.is_empty() {
         String::new()
@@ -187,14 +205,15 @@ fn draw_top_bar(f: &mut Frame, app: &App, area: Rect) {
     };
 
     if !refresh_info.is_empty() {
-        let used: usize = spans.iter().map(|s| s.content.len()).sum();
-        let pad = (area.width as usize).saturating_sub(used + refresh_info.len() + 1);
+        let used: usize = spans.iter().map(|s| s.content.chars().count()).sum();
+        let pad = (area.width as usize).saturating_sub(used + refresh_info.chars().count() + 1);
         if pad > 0 {
             spans.push(Span::raw(" ".repeat(pad)));
         }
+        let color = if stale.is_some() { t.error } else { t.dim };
         spans.push(Span::styled(
             refresh_info,
-            Style::default().fg(t.dim),
+            Style::default().fg(color),
         ));
     }
 
@@ -235,9 +254,31 @@ fn draw_main(f: &mut Frame, app: &mut App, area: Rect) {
         }
     }
 
+    // Allocation panel beside the Portfolio table when both fit, else below it
+    let area = if app.tab == Tab::Portfolio && app.allocation_open {
+        let beside = area.width >= 190;
+        let chunks = Layout::default()
+            .direction(if beside { Direction::Horizontal } else { Direction::Vertical })
+            .constraints(if beside {
+                [Constraint::Min(40), Constraint::Length(46)]
+            } else {
+                [Constraint::Min(5), Constraint::Length(area.height / 2)]
+            })
+            .split(area);
+        draw_allocation(f, app, chunks[1], beside);
+        chunks[0]
+    } else {
+        area
+    };
+
     let table_height = area.height.saturating_sub(2) as usize;
     app.page_height = table_height.max(1);
 
+    if app.tab == Tab::Discover && !app.treemap {
+        draw_discover(f, app, area);
+        return;
+    }
+
     let visible = app.visible_coins();
 
     if visible.is_empty() {
@@ -246,6 +287,8 @@ fn draw_main(f: &mut Frame, app: &mut App, area: Rect) {
             Tab::Portfolio => "  No holdings. Press 'a' to add a holding.",
             _ => if !app.filter_query.is_empty() {
                 "  No matches for filter."
+            } else if app.market_category.is_some() {
+                if app.loading { "  Loading sector..." } else { "  No coins in this sector." }
             } else {
                 "  No data."
             },
@@ -255,6 +298,11 @@ fn draw_main(f: &mut Frame, app: &mut App, area: Rect) {
         return;
     }
 
+    if app.treemap {
+        draw_treemap(f, app, area);
+        return;
+    }
+
     let is_portfolio = app.tab == Tab::Portfolio;
 
     let header_cells = {
@@ -312,14 +360,23 @@ fn draw_main(f: &mut Frame, app: &mut App, area: Rect) {
             let h1 = format_pct(coin.price_change_percentage_1h_in_currency);
             let h24 = format_pct(coin.price_change_percentage_24h_in_currency);
             let d7 = format_pct(coin.price_change_percentage_7d_in_currency);
-            let hi24 = coin.high_24h.map(|v| format_price(v)).unwrap_or_else(|| "--".into());
-            let lo24 = coin.low_24h.map(|v| format_price(v)).unwrap_or_else(|| "--".into());
+            let hi24 = coin.high_24h.map(format_price).unwrap_or_else(|| "--".into());
+            let lo24 = coin.low_24h.map(format_price).unwrap_or_else(|| "--".into());
             let vol = format_large(coin.total_volume);
             let mcap = format_large(coin.market_cap);
 
+            let name_cell = if app.compare_ids.contains(&coin.id) {
+                Cell::from(Line::from(vec![
+                    Span::styled("\u{25c6} ", Style::default().fg(accent)),
+                    Span::styled(name, Style::default().fg(fg)),
+                ]))
+            } else {
+                Cell::from(name).style(Style::default().fg(fg))
+            };
+
             let mut cells = vec![
                 Cell::from(rank).style(Style::default().fg(dim)),
-                Cell::from(name).style(Style::default().fg(fg)),
+                name_cell,
                 Cell::from(symbol).style(Style::default().fg(accent)),
                 Cell::from(price).style(Style::default().fg(fg)),
                 pct_cell(coin.price_change_percentage_1h_in_currency, &h1, positive, negative, dim),
@@ -404,6 +461,29 @@ fn draw_main(f: &mut Frame, app: &mut App, area: Rect) {
 
     let mut block = Block::default().borders(Borders::NONE);
 
+    if let (Tab::Markets, Some(category)) = (app.tab, &app.market_category) {
+        let change = category.market_cap_change_24h;
+        block = block.title(Line::from(vec![
+            Span::styled(" Sector: ", Style::default().fg(t.dim)),
+            Span::styled(
+                format!("{} ", category.name),
+                Style::default().fg(t.title).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("MCap(USD):{} ", category.market_cap.map(format_large).unwrap_or_else(|| "--".into())),
+                Style::default().fg(t.dim),
+            ),
+            Span::styled(
+                format!("{} ", format_pct(change)),
+                Style::default().fg(match change {
+                    Some(p) if p > 0.0 => t.positive,
+                    Some(p) if p < 0.0 => t.negative,
+                    _ => t.dim,
+                }),
+            ),
+        ])).title_alignment(ratatui::layout::Alignment::Right);
+    }
+
     if is_portfolio {
         let total = app.total_portfolio_value();
         block = block.title(Line::from(vec![
@@ -423,6 +503,314 @@ fn draw_main(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_widget(table, area);
 }
 
+/// Trending, top gainers and top losers side by side, or stacked on narrow
+/// terminals. The selection runs through the panes in order.
+fn draw_discover(f: &mut Frame, app: &App, area: Rect) {
+    let t = &app.theme;
+    let window = app.change_window;
+    let sections = app.discover_sections();
+    let titles = [
+        " Trending ".to_string(),
+        format!(" Top gainers {} ", window.label()),
+        format!(" Top losers {} ", window.label()),
+    ];
+    let direction = if area.width >= 120 { Direction::Horizontal } else { Direction::Vertical };
+    let panes = Layout::default()
+        .direction(direction)
+        .constraints([Constraint::Ratio(1, 3), Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)])
+        .split(area);
+
+    let mut offset = 0;
+    for (i, coins) in sections.iter().enumerate() {
+        let block = Block::default()
+            .title(Span::styled(titles[i].clone(), Style::default().fg(t.title).add_modifier(Modifier::BOLD)))
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(t.border));
+        let inner = block.inner(panes[i]);
+        f.render_widget(block, panes[i]);
+
+        if coins.is_empty() {
+            let msg = if !app.filter_query.is_empty() {
+                "  No matches for filter.".to_string()
+            } else if i == 0 {
+                "  Loading trending coins...".to_string()
+            } else {
+                format!("  No coins {} over {}.", if i == 1 { "up" } else { "down" }, window.label())
+            };
+            f.render_widget(Paragraph::new(msg).style(Style::default().fg(t.dim)), inner);
+            continue;
+        }
+
+        // Keep this pane's part of the selection in view
+        let page = inner.height.saturating_sub(1).max(1) as usize;
+        let local = app.selected.checked_sub(offset).filter(|l| *l < coins.len());
+        let first = local.map_or(0, |l| l.saturating_sub(page - 1));
+
+        let header = Row::new(
+            ["#", "Name", "Ticker", "Price", window.label()]
+                .into_iter()
+                .map(|h| Cell::from(h).style(Style::default().fg(t.dim))),
+        );
+        let rows: Vec<Row> = coins
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(page)
+            .map(|(j, coin)| {
+                let change = window.change(coin);
+                let name = if app.compare_ids.contains(&coin.id) {
+                    Line::from(vec![
+                        Span::styled("\u{25c6} ", Style::default().fg(t.accent)),
+                        Span::styled(coin.name.clone(), Style::default().fg(t.fg)),
+                    ])
+                } else {
+                    Line::styled(coin.name.clone(), Style::default().fg(t.fg))
+                };
+                let row = Row::new(vec![
+                    Cell::from(coin.market_cap_rank.map(|r| r.to_string()).unwrap_or_default())
+                        .style(Style::default().fg(t.dim)),
+                    Cell::from(name),
+                    Cell::from(coin.symbol.to_uppercase()).style(Style::default().fg(t.accent)),
+                    Cell::from(format_price(coin.current_price)).style(Style::default().fg(t.fg)),
+                    pct_cell(change, &format_pct(change), t.positive, t.negative, t.dim),
+                ]);
+                if local == Some(j) {
+                    row.style(Style::default().bg(t.highlight_bg).fg(t.highlight_fg))
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Length(5),
+            Constraint::Min(10),
+            Constraint::Length(6),
+            Constraint::Length(11),
+            Constraint::Length(8),
+        ];
+        f.render_widget(Table::new(rows, widths).header(header).column_spacing(1), inner);
+        offset += coins.len();
+    }
+}
+
+/// Coins as blocks sized by market cap, or by holding value on the Portfolio
+/// tab, and shaded by price change over `change_window`.
+fn draw_treemap(f: &mut Frame, app: &mut App, area: Rect) {
+    let window = app.change_window;
+    let is_portfolio = app.tab == Tab::Portfolio;
+    // A coin can sit in more than one Discover pane; it gets one block
+    let mut seen = HashSet::new();
+    let items: Vec<(f64, String, String, Option<f64>, String)> = app
+        .visible_coins()
+        .iter()
+        .map(|(_, c)| {
+            let (weight, figure) = if is_portfolio {
+                let value = app.holding_for(&c.id) * c.current_price;
+                (value, format_price(value))
+            } else {
+                (c.market_cap, format_large(c.market_cap))
+            };
+            let weight = if seen.insert(c.id.as_str()) { weight } else { 0.0 };
+            (weight, c.name.clone(), c.symbol.to_uppercase(), window.change(c), figure)
+        })
+        .collect();
+    let t = &app.theme;
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(area);
+    let header = format!(
+        " size: {} \u{b7} colour: {} change (w) \u{b7} t table",
+        if is_portfolio { "holding value" } else { "market cap" },
+        window.label(),
+    );
+    f.render_widget(Paragraph::new(header).style(Style::default().fg(t.dim)), chunks[0]);
+
+    // Moves at or beyond this size get a solid block
+    let strong = match window {
+        ChangeWindow::Hour1 => 1.0,
+        ChangeWindow::Day1 => 3.0,
+        ChangeWindow::Day7 => 10.0,
+    };
+    let weights: Vec<f64> = items.iter().map(|i| i.0).collect();
+    let rects = squarify(&weights, chunks[1]);
+    for (i, ((_, name, symbol, change, figure), rect)) in items.iter().zip(&rects).enumerate() {
+        if rect.width == 0 || rect.height == 0 {
+            continue;
+        }
+        // Leave a gap on the right and, where there is room, below
+        let block = Rect {
+            width: rect.width.saturating_sub(1).max(1),
+            height: if rect.height >= 3 { rect.height - 1 } else { rect.height },
+            ..*rect
+        };
+        let color = match change {
+            Some(p) if *p > 0.0 => t.positive,
+            Some(p) if *p < 0.0 => t.negative,
+            _ => t.dim,
+        };
+        let style = if i == app.selected {
+            Style::default().fg(t.accent).add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else if change.is_some_and(|p| p.abs() >= strong) {
+            Style::default().fg(color).add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().fg(color).bg(t.highlight_bg)
+        };
+        let label = if (block.width as usize) >= name.chars().count() + 2 && block.height >= 3 {
+            name.as_str()
+        } else {
+            symbol.as_str()
+        };
+        let mut lines = vec![Line::from(format!(" {}", label)).style(Style::default().add_modifier(Modifier::BOLD))];
+        if block.height >= 2 {
+            lines.push(Line::from(format!(" {}", format_pct(*change))));
+        }
+        if block.height >= 3 {
+            lines.push(Line::from(format!(" {}", figure)));
+        }
+        f.render_widget(Paragraph::new(lines).style(style), block);
+    }
+    app.treemap_areas = rects;
+}
+
+/// Squarified treemap: split `area` into one rectangle per weight, in input
+/// order, with areas proportional to the weights and shapes kept close to
+/// square. Cells are about twice as tall as wide, which the layout accounts
+/// for. Weights too small for a cell get an empty rectangle.
+fn squarify(weights: &[f64], area: Rect) -> Vec<Rect> {
+    let mut out = vec![Rect::default(); weights.len()];
+    let mut order: Vec<usize> = (0..weights.len()).filter(|&i| weights[i] > 0.0).collect();
+    order.sort_by(|&a, &b| weights[b].partial_cmp(&weights[a]).unwrap_or(std::cmp::Ordering::Equal));
+    let total: f64 = order.iter().map(|&i| weights[i]).sum();
+    if total <= 0.0 || area.width == 0 || area.height == 0 {
+        return out;
+    }
+
+    // Lay out in square units: one cell is 1 wide and 2 tall
+    let (mut x, mut y) = (0.0, 0.0);
+    let (mut w, mut h) = (area.width as f64, area.height as f64 * 2.0);
+    let scale = w * h / total;
+    let worst = |row: &[f64], side: f64| {
+        let sum: f64 = row.iter().sum();
+        row.iter()
+            .map(|&a| (side * side * a / (sum * sum)).max(sum * sum / (side * side * a)))
+            .fold(0.0, f64::max)
+    };
+
+    let mut rest = &order[..];
+    while !rest.is_empty() {
+        let side = w.min(h);
+        let mut row: Vec<f64> = Vec::new();
+        let mut n = 0;
+        for &i in rest {
+            let a = weights[i] * scale;
+            let mut next = row.clone();
+            next.push(a);
+            if !row.is_empty() && worst(&next, side) > worst(&row, side) {
+                break;
+            }
+            row = next;
+            n += 1;
+        }
+        let sum: f64 = row.iter().sum();
+        let thickness = if side > 0.0 { sum / side } else { 0.0 };
+        let mut pos = 0.0;
+        for (&i, a) in rest[..n].iter().zip(&row) {
+            let start = pos;
+            pos += a / thickness;
+            let (fx0, fx1, fy0, fy1) = if w >= h {
+                (x, x + thickness, y + start, y + pos)
+            } else {
+                (x + start, x + pos, y, y + thickness)
+            };
+            // Snap both edges to cells, computing each shared edge the same
+            // way on both sides so neighbours meet without gaps or overlap
+            let snap = |v: f64, max: u16| (v.round().max(0.0) as u16).min(max);
+            let (x0, x1) = (snap(fx0, area.width), snap(fx1, area.width));
+            let (y0, y1) = (snap(fy0 / 2.0, area.height), snap(fy1 / 2.0, area.height));
+            out[i] = Rect::new(area.x + x0, area.y + y0, x1.saturating_sub(x0), y1.saturating_sub(y0));
+        }
+        if w >= h {
+            x += thickness;
+            w -= thickness;
+        } else {
+            y += thickness;
+            h -= thickness;
+        }
+        rest = &rest[n..];
+    }
+    out
+}
+
+/// Share of the portfolio value per asset class and per coin.
+fn draw_allocation(f: &mut Frame, app: &App, area: Rect, beside: bool) {
+    let t = &app.theme;
+    let block = Block::default()
+        .title(Span::styled(" Allocation ", Style::default().fg(t.title).add_modifier(Modifier::BOLD)))
+        .borders(if beside { Borders::LEFT } else { Borders::TOP })
+        .border_style(Style::default().fg(t.border))
+        .padding(ratatui::widgets::Padding::left(1));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let by_coin = app.allocation_by_coin();
+    let total: f64 = by_coin.iter().map(|(_, v)| v).sum();
+    if total <= 0.0 {
+        f.render_widget(Paragraph::new("No priced holdings yet.").style(Style::default().fg(t.dim)), inner);
+        return;
+    }
+
+    // Label, bar, percentage
+    const LABEL_W: usize = 12;
+    let bar_w = (inner.width as usize).saturating_sub(LABEL_W + 8);
+    let row = |label: &str, value: f64, color: Color| {
+        let frac = value / total;
+        Line::from(vec![
+            Span::styled(format!("{:<w$}", label, w = LABEL_W), Style::default().fg(t.fg)),
+            Span::styled(format!("{:<w$}", bar(frac, bar_w), w = bar_w), Style::default().fg(color)),
+            Span::styled(format!("{:>7.1}%", frac * 100.0), Style::default().fg(t.dim)),
+        ])
+    };
+
+    let mut lines = vec![Line::from(Span::styled("By category", Style::default().fg(t.dim)))];
+    for (class, value) in app.allocation_by_class() {
+        let color = match class {
+            AssetClass::Stablecoin => t.dim,
+            AssetClass::Major => t.accent,
+            AssetClass::Alt => t.input_accent,
+        };
+        lines.push(row(class.label(), value, color));
+    }
+    lines.push(Line::from(""));
+    let group = if app.allocation_group {
+        format!("O: ungroup < {}%", ALLOCATION_OTHER_PCT)
+    } else {
+        format!("O: group < {}%", ALLOCATION_OTHER_PCT)
+    };
+    lines.push(Line::from(vec![
+        Span::styled("By coin ", Style::default().fg(t.dim)),
+        Span::styled(format!("({})", group), Style::default().fg(t.border)),
+    ]));
+    for (label, value) in &by_coin {
+        let color = if label.starts_with("Other") { t.dim } else { t.accent };
+        lines.push(row(label, *value, color));
+    }
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// A horizontal bar `frac` of `width` cells long, in eighths of a cell.
+fn bar(frac: f64, width: usize) -> String {
+    const PARTS: [char; 8] = ['\u{258f}', '\u{258e}', '\u{258d}', '\u{258c}', '\u{258b}', '\u{258a}', '\u{2589}', '\u{2588}'];
+    let eighths = (frac.clamp(0.0, 1.0) * width as f64 * 8.0).round() as usize;
+    let mut s = "\u{2588}".repeat(eighths / 8);
+    if let Some(part) = (eighths % 8).checked_sub(1) {
+        s.push(PARTS[part]);
+    }
+    s
+}
+
 // -- Bottom bar --
 
 fn draw_bottom_bar(f: &mut Frame, app: &App, area: Rect) {
@@ -445,15 +833,22 @@ fn draw_bottom_bar(f: &mut Frame, app: &App, area: Rect) {
         return;
     }
 
-    let hints = if app.popup_open {
-        " Esc close | h/l cycle view "
+    let hints = if app.compare_open {
+        " Esc close | Tab range | L log "
+    } else if app.popup_open {
+        " Esc close | Tab range | c candles | L log | m avg | 1-4 indicators | h/l inspect "
     } else if app.input_mode == InputMode::Settings {
         " j/k navigate | Enter edit | s save | Esc cancel "
+    } else if app.treemap && app.input_mode == InputMode::Normal {
+        " j/k select | Enter detail | f fav | a hold | A alert | x cmp | w 1h/24h/7d | t table | click select "
+    } else if app.input_mode == InputMode::Sectors {
+        " \u{2191}/\u{2193} move | type to filter | Tab sort | Enter show | Esc close "
     } else {
         match app.tab {
-            Tab::Markets => " j/k \u{2195} | Tab \u{21c6} | Enter detail | f fav | a hold | / filter | s sort | A alert | c add | S set | q quit ",
-            Tab::Favourites => " j/k \u{2195} | Tab \u{21c6} | Enter detail | f unfav | a hold | / filter | s sort | A alert | c add | S set | q quit ",
-            Tab::Portfolio => " j/k \u{2195} | Tab \u{21c6} | Enter detail | a edit | d rm | b buy$ | / filter | s sort | A alert | c add | S set | q quit ",
+            Tab::Markets => " j/k \u{2195} | Tab \u{21c6} | Enter detail | f fav | a hold | / filter | s sort | t map | A alert | x cmp | o sectors | c add | S set | q quit ",
+            Tab::Favourites => " j/k \u{2195} | Tab \u{21c6} | Enter detail | f unfav | a hold | / filter | s sort | t map | A alert | x cmp | c add | S set | q quit ",
+            Tab::Portfolio => " j/k \u{2195} | Tab \u{21c6} | Enter detail | a edit | d rm | b buy$ | p alloc | / filter | s sort | t map | A alert | x cmp | c add | S set | q quit ",
+            Tab::Discover => " j/k \u{2195} | h/l pane | Tab \u{21c6} | Enter detail | f fav | a hold | w 1h/24h/7d | / filter | A alert | x cmp | S set | q quit ",
         }
     };
 
@@ -467,6 +862,20 @@ fn draw_bottom_bar(f: &mut Frame, app: &App, area: Rect) {
         ));
     }
 
+    if app.can_load_more() && !app.popup_open {
+        spans.push(Span::styled(
+            format!(" [{}/{} \u{2193} more]", app.markets_loaded, app.config.markets_limit),
+            Style::default().fg(t.dim),
+        ));
+    }
+
+    if !app.compare_ids.is_empty() && !app.compare_open {
+        spans.push(Span::styled(
+            format!(" [compare {}: C]", app.compare_ids.len()),
+            Style::default().fg(t.accent),
+        ));
+    }
+
     if let Some(ref err) = app.error {
         spans.push(Span::styled(
             format!(" \u{2502} {}", err),
@@ -480,56 +889,78 @@ fn draw_bottom_bar(f: &mut Frame, app: &App, area: Rect) {
 
 // -- Chart popup --
 
-fn draw_popup(f: &mut Frame, app: &App) {
+/// Draws the coin detail popup and returns the chart's plot area, if any.
+fn draw_popup(f: &mut Frame, app: &App) -> Option<Rect> {
     let t = &app.theme;
-    let coin = match app.selected_coin() {
-        Some(c) => c,
-        None => return,
-    };
+    let coin = app.selected_coin()?;
 
-    let area = centered_rect(75, 65, f.area());
+    let area = centered_rect(85, 85, f.area());
     f.render_widget(Clear, area);
 
-    let title = format!(" {} ({}) - {} ", coin.name, coin.symbol.to_uppercase(), app.chart_view.label());
+    let title = format!(
+        " {} ({}) - {} {}{} ",
+        coin.name,
+        coin.symbol.to_uppercase(),
+        app.chart_view.label(),
+        app.chart_mode.label(),
+        if app.chart_log_scale() { " log" } else { "" }
+    );
+    let title = match app.chart_ma {
+        MovingAverage::Off => title,
+        ma => {
+            let periods: Vec<String> = MovingAverage::PERIODS.iter().map(|p| p.to_string()).collect();
+            format!("{}\u{b7} {} {} ", title, ma.label(), periods.join("/"))
+        }
+    };
 
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(t.accent));
 
-    let inner = block.inner(area);
+    let block_inner = block.inner(area);
     f.render_widget(block, area);
 
+    // Fundamentals beside the chart when there is room, otherwise under it
+    let beside = block_inner.width >= 110;
+    let (inner, details_area) = if beside {
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(60), Constraint::Length(46)])
+            .split(block_inner);
+        (cols[0], cols[1])
+    } else {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(10), Constraint::Length(block_inner.height / 3)])
+            .split(block_inner);
+        (rows[0], rows[1])
+    };
+    draw_coin_details(f, app, coin, details_area, beside);
+
     let cache_key = (coin.id.clone(), app.chart_view.days());
 
-    // Build info section with supply + alerts
     let mut info_lines: Vec<Line> = Vec::new();
 
-    // Supply info line
-    let circ = coin.circulating_supply.map(|v| format_large(v)).unwrap_or_else(|| "--".into());
-    let max_s = coin.max_supply.map(|v| format_large(v)).unwrap_or_else(|| "\u{221e}".into());
-    info_lines.push(Line::from(vec![
-        Span::styled(format!(" Supply: {} / {} ", circ, max_s), Style::default().fg(t.dim)),
-    ]));
-
-    // Active alerts for this coin
-    let coin_alerts: Vec<&PriceAlert> = app.alerts.iter().filter(|a| a.coin_id == coin.id && !a.triggered).collect();
-    if !coin_alerts.is_empty() {
-        let mut alert_spans = vec![Span::styled(" Alerts: ", Style::default().fg(t.dim))];
-        for (i, alert) in coin_alerts.iter().enumerate() {
-            if i > 0 {
-                alert_spans.push(Span::styled(", ", Style::default().fg(t.dim)));
-            }
-            let dir = match alert.direction {
-                AlertDirection::Above => "\u{25b2}",
-                AlertDirection::Below => "\u{25bc}",
-            };
-            alert_spans.push(Span::styled(
-                format!("{}{}", dir, format_price(alert.target_price)),
-                Style::default().fg(t.accent),
-            ));
-        }
-        info_lines.push(Line::from(alert_spans));
+    // Realised volatility and max drawdown over the range
+    if let Some(history) = app.chart_cache.get(&cache_key).filter(|_| app.chart_indicators.risk) {
+        let span = match (history.timestamps.first(), history.timestamps.last()) {
+            (Some(a), Some(b)) if b > a => (b - a) as f64,
+            _ => 0.0,
+        };
+        let samples_per_year = if span > 0.0 {
+            (history.prices.len() - 1) as f64 * 365.0 * 86400.0 / span
+        } else {
+            0.0
+        };
+        let vol = indicators::volatility(&history.prices, samples_per_year)
+            .map(|v| format!("{:.1}%", v * 100.0))
+            .unwrap_or_else(|| "--".into());
+        let drawdown = indicators::max_drawdown(&history.prices) * 100.0;
+        info_lines.push(Line::from(Span::styled(
+            format!(" Volatility (ann.): {}  Max drawdown: -{:.1}% ", vol, drawdown),
+            Style::default().fg(t.dim),
+        )));
     }
 
     let info_height = info_lines.len() as u16;
@@ -539,7 +970,7 @@ fn draw_popup(f: &mut Frame, app: &App) {
             let msg = Paragraph::new("  No price data available.")
                 .style(Style::default().fg(t.dim));
             f.render_widget(msg, inner);
-            return;
+            return None;
         }
 
         let chunks = Layout::default()
@@ -547,7 +978,7 @@ fn draw_popup(f: &mut Frame, app: &App) {
             .constraints([
                 Constraint::Length(3),           // stats
                 Constraint::Min(3),              // chart
-                Constraint::Length(info_height),  // supply + alerts
+                Constraint::Length(info_height),  // volatility + drawdown
             ])
             .split(inner);
 
@@ -560,7 +991,12 @@ fn draw_popup(f: &mut Frame, app: &App) {
         let change_color = if change_pct >= 0.0 { t.positive } else { t.negative };
         let sign = if change_pct >= 0.0 { "+" } else { "" };
 
-        let stats = Paragraph::new(Line::from(vec![
+        let candles = match app.chart_mode {
+            ChartMode::Candles => app.ohlc_cache.get(&cache_key).map(Vec::as_slice).filter(|c| !c.is_empty()),
+            ChartMode::Line => None,
+        };
+
+        let mut stats_lines = vec![Line::from(vec![
             Span::styled(format!(" Price: {} ", format_price(last)), Style::default().fg(t.fg)),
             Span::styled(
                 format!(" {}{:.2}% ", sign, change_pct),
@@ -570,149 +1006,915 @@ fn draw_popup(f: &mut Frame, app: &App) {
                 format!(" Lo: {}  Hi: {} ", format_price(min), format_price(max)),
                 Style::default().fg(t.dim),
             ),
-        ]));
-        f.render_widget(stats, chunks[0]);
-
-        let spark_width = chunks[1].width as usize;
-        let spark_height = chunks[1].height as usize;
-        let resolution = spark_height.max(1) as f64 * 8.0;
-
-        let sampled = downsample(&history.prices, spark_width);
-
-        let spark_data: Vec<u64> = {
-            let min_p = sampled.iter().cloned().fold(f64::INFINITY, f64::min);
-            let max_p = sampled.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-            let range = max_p - min_p;
-            if range == 0.0 {
-                vec![(resolution / 2.0) as u64; sampled.len()]
-            } else {
-                sampled
-                    .iter()
-                    .map(|p| ((p - min_p) / range * resolution) as u64)
-                    .collect()
+        ])];
+        if let Some(i) = app.chart_cursor.filter(|i| *i < history.prices.len()) {
+            let price = history.prices[i];
+            let since_start = if first > 0.0 { (price - first) / first * 100.0 } else { 0.0 };
+            stats_lines.push(Line::from(vec![
+                Span::styled(
+                    format!(" \u{25b8} {} ", format_time(history.timestamps[i], "%Y-%m-%d %H:%M")),
+                    Style::default().fg(t.accent),
+                ),
+                Span::styled(format!(" {} ", format_price(price)), Style::default().fg(t.fg)),
+                Span::styled(format!(" {:+.2}% from start ", since_start), Style::default().fg(t.dim)),
+            ]));
+            let mut detail = Vec::new();
+            if let Some(c) = candles.and_then(|c| {
+                // The candle whose period contains the cursor
+                let ts = history.timestamps[i];
+                c.iter().find(|c| c.ts >= ts).or(c.last())
+            }) {
+                detail.push(Span::styled(
+                    format!(
+                        "   O {}  H {}  L {}  C {} ",
+                        format_price(c.open),
+                        format_price(c.high),
+                        format_price(c.low),
+                        format_price(c.close)
+                    ),
+                    Style::default().fg(t.dim),
+                ));
             }
-        };
-
-        let spark_color = if change_pct >= 0.0 { t.positive } else { t.negative };
+            if let Some(v) = history.volumes.get(i).filter(|v| **v > 0.0) {
+                detail.push(Span::styled(format!("   24h vol {} ", format_large(*v)), Style::default().fg(t.dim)));
+            }
+            if !detail.is_empty() {
+                stats_lines.push(Line::from(detail));
+            }
+        } else {
+            stats_lines.push(Line::from(Span::styled(
+                " h/l or click to inspect",
+                Style::default().fg(t.dim),
+            )));
+        }
+        f.render_widget(Paragraph::new(stats_lines), chunks[0]);
 
-        let sparkline = Sparkline::default()
-            .data(&spark_data)
-            .style(Style::default().fg(spark_color));
-        f.render_widget(sparkline, chunks[1]);
+        let line_color = if change_pct >= 0.0 { t.positive } else { t.negative };
+        let plot = draw_price_chart(f, app, history, candles, chunks[1], line_color);
 
         // Info section
         let info_p = Paragraph::new(info_lines);
         f.render_widget(info_p, chunks[2]);
+        return plot;
     } else if app.loading_chart {
         let msg = Paragraph::new("  Loading chart data...")
             .style(Style::default().fg(t.dim));
         f.render_widget(msg, inner);
     } else {
-        let msg = Paragraph::new("  No data. Press h/l to reload.")
+        let msg = Paragraph::new("  No data. Press Tab to switch range and retry.")
             .style(Style::default().fg(t.dim));
         f.render_widget(msg, inner);
     }
+    None
 }
 
-// -- Amount input popup --
-
-fn draw_input_popup(f: &mut Frame, app: &App) {
+/// Market data, the user's position and the coin's profile, with a rule
+/// separating it from the chart `beside` or above it.
+fn draw_coin_details(f: &mut Frame, app: &App, coin: &Coin, area: Rect, beside: bool) {
     let t = &app.theme;
-    let coin = match app.selected_coin() {
-        Some(c) => c,
-        None => return,
-    };
-
-    let area = centered_rect(40, 5, f.area());
-    let area = Rect {
-        height: area.height.max(5),
-        ..area
-    };
-    f.render_widget(Clear, area);
-
-    let title = format!(" {} amount ", coin.symbol.to_uppercase());
-    let block = Block::default()
-        .title(title)
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(t.input_accent));
+    let dim = Style::default().fg(t.dim);
+    let fg = Style::default().fg(t.fg);
+    let heading = |s: &'static str| Line::from(Span::styled(s, Style::default().fg(t.accent).add_modifier(Modifier::BOLD)));
+    let opt_large = |v: Option<f64>| v.filter(|v| *v > 0.0).map(format_large).unwrap_or_else(|| "--".into());
+    let sym = currency_symbol(&app.config.currency);
+
+    let mut lines = vec![heading("Market")];
+    lines.push(Line::from(vec![
+        Span::styled(" Rank ", dim),
+        Span::styled(coin.market_cap_rank.map(|r| format!("#{}", r)).unwrap_or_else(|| "--".into()), fg),
+        Span::styled("  MCap ", dim),
+        Span::styled(format!("{}{}", sym, format_large(coin.market_cap)), fg),
+        Span::styled("  FDV ", dim),
+        Span::styled(format!("{}{}", sym, opt_large(coin.fully_diluted_valuation)), fg),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(" Volume 24h ", dim),
+        Span::styled(format!("{}{}", sym, format_large(coin.total_volume)), fg),
+    ]));
+    for (label, extreme, date) in [("ATH", coin.ath, &coin.ath_date), ("ATL", coin.atl, &coin.atl_date)] {
+        let Some(extreme) = extreme.filter(|v| *v > 0.0) else { continue };
+        let distance = (coin.current_price / extreme - 1.0) * 100.0;
+        let color = if distance >= 0.0 { t.positive } else { t.negative };
+        let distance = if distance.abs() >= 1000.0 {
+            let sign = if distance >= 0.0 { "+" } else { "-" };
+            format!("{}{}%", sign, add_commas(&format!("{:.0}", distance.abs())))
+        } else {
+            format!("{:+.1}%", distance)
+        };
+        let when = date
+            .as_deref()
+            .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok())
+            .map(|d| d.format("%b %d %Y").to_string())
+            .unwrap_or_default();
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {} ", label), dim),
+            Span::styled(format_price(extreme), fg),
+            Span::styled(format!(" {} ", distance), Style::default().fg(color)),
+            Span::styled(when, dim),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(" Supply ", dim),
+        Span::styled(opt_large(coin.circulating_supply), fg),
+        Span::styled(" circ / ", dim),
+        Span::styled(opt_large(coin.total_supply), fg),
+        Span::styled(" total / ", dim),
+        Span::styled(coin.max_supply.map(format_large).unwrap_or_else(|| "\u{221e}".into()), fg),
+        Span::styled(" max", dim),
+    ]));
 
-    let inner = block.inner(area);
-    f.render_widget(block, area);
+    let amount = app.holding_for(&coin.id);
+    let coin_alerts: Vec<&PriceAlert> = app.alerts.iter().filter(|a| a.coin_id == coin.id && !a.triggered).collect();
+    if amount > 0.0 || !coin_alerts.is_empty() {
+        lines.push(Line::default());
+        lines.push(heading("Your position"));
+    }
+    if amount > 0.0 {
+        lines.push(Line::from(vec![
+            Span::styled(" Holding ", dim),
+            Span::styled(format!("{} {}", format_amount(amount), coin.symbol.to_uppercase()), fg),
+            Span::styled(" = ", dim),
+            Span::styled(format!("{}{}", sym, format_price(amount * coin.current_price)), Style::default().fg(t.accent)),
+        ]));
+        if let Some(buy) = app.buy_price_for(&coin.id).filter(|b| *b > 0.0) {
+            let pnl = (coin.current_price / buy - 1.0) * 100.0;
+            let color = if pnl >= 0.0 { t.positive } else { t.negative };
+            lines.push(Line::from(vec![
+                Span::styled(" Bought at ", dim),
+                Span::styled(format_price(buy), fg),
+                Span::styled(format!(" {:+.1}%", pnl), Style::default().fg(color)),
+            ]));
+        }
+    }
+    if !coin_alerts.is_empty() {
+        let mut spans = vec![Span::styled(" Alerts ", dim)];
+        for (i, alert) in coin_alerts.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled(", ", dim));
+            }
+            spans.push(Span::styled(alert_label(alert), Style::default().fg(t.accent)));
+            if let Some(ts) = alert.created_at {
+                spans.push(Span::styled(format!(" set {}", format_time(ts, "%b %d")), dim));
+            }
+        }
+        lines.push(Line::from(spans));
+    }
 
-    let text = if app.input_buf.is_empty() {
-        "Enter amount (0 to remove): _"
-    } else {
-        &app.input_buf
-    };
+    lines.push(Line::default());
+    lines.push(heading("About"));
+    match app.coin_details.get(&coin.id) {
+        Some(details) => {
+            if !details.categories.is_empty() {
+                lines.push(Line::from(vec![
+                    Span::styled(" Categories ", dim),
+                    Span::styled(details.categories.join(", "), fg),
+                ]));
+            }
+            if let Some(ref home) = details.homepage {
+                lines.push(Line::from(vec![Span::styled(" Web ", dim), Span::styled(home.clone(), fg)]));
+            }
+            for explorer in &details.explorers {
+                lines.push(Line::from(vec![Span::styled(" Explorer ", dim), Span::styled(explorer.clone(), fg)]));
+            }
+            if !details.description.is_empty() {
+                lines.push(Line::default());
+                for para in details.description.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+                    lines.push(Line::from(Span::styled(format!(" {}", para), fg)));
+                }
+            }
+        }
+        None => lines.push(Line::from(Span::styled(" Loading profile...", dim))),
+    }
 
-    let input = Paragraph::new(format!(" {}_", text.trim_end_matches('_')))
-        .style(Style::default().fg(t.fg));
-    f.render_widget(input, inner);
+    let block = Block::default()
+        .borders(if beside { Borders::LEFT } else { Borders::TOP })
+        .border_style(Style::default().fg(t.border))
+        .padding(ratatui::widgets::Padding::left(1));
+    f.render_widget(
+        Paragraph::new(lines).block(block).wrap(ratatui::widgets::Wrap { trim: false }),
+        area,
+    );
 }
 
-// -- Alert popup --
-
-fn draw_alert_popup(f: &mut Frame, app: &App) {
+/// Price line or candles on a linear or log axis, with price labels on the
+/// left and volume bars, indicator panels and a time axis underneath.
+/// Returns the plot area so mouse clicks can be mapped back to points.
+fn draw_price_chart(
+    f: &mut Frame,
+    app: &App,
+    history: &PriceHistory,
+    candles: Option<&[Candle]>,
+    area: Rect,
+    color: Color,
+) -> Option<Rect> {
     let t = &app.theme;
-    let coin = match app.selected_coin() {
-        Some(c) => c,
-        None => return,
+    let (&t0, &t1) = (history.timestamps.first()?, history.timestamps.last()?);
+    let shown = app.chart_indicators;
+    let bands = if shown.bollinger { indicators::bollinger(&history.prices, 20, 2.0) } else { Vec::new() };
+    let (mut min, mut max) = match candles {
+        Some(c) => (
+            c.iter().map(|c| c.low).fold(f64::INFINITY, f64::min),
+            c.iter().map(|c| c.high).fold(f64::NEG_INFINITY, f64::max),
+        ),
+        None => (
+            history.prices.iter().cloned().fold(f64::INFINITY, f64::min),
+            history.prices.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        ),
     };
-
-    let area = centered_rect(45, 5, f.area());
-    let area = Rect { height: area.height.max(7), ..area };
-    f.render_widget(Clear, area);
-
-    let title = format!(" {} alert ", coin.symbol.to_uppercase());
-    let block = Block::default()
-        .title(title)
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(t.input_accent));
-
-    let inner = block.inner(area);
-    f.render_widget(block, area);
-
-    let chunks = Layout::default()
+    for band in bands.iter().flatten() {
+        min = min.min(band.lower);
+        max = max.max(band.upper);
+    }
+    let (lo, hi) = if max > min { (min, max) } else { (min - 1.0, max + 1.0) };
+    // Log scale needs positive prices; fall back to linear otherwise
+    let log = app.chart_log_scale() && lo > 0.0;
+    let scale = |p: f64| if log { p.ln() } else { p };
+    let (slo, shi) = (scale(lo), scale(hi));
+    let mid = if log { (lo * hi).sqrt() } else { (hi + lo) / 2.0 };
+
+    let y_labels = [format_price(hi), format_price(mid), format_price(lo)];
+    let label_w = y_labels.iter().map(|l| l.len()).max().unwrap_or(0) as u16 + 2;
+    let has_volume = history.volumes.iter().any(|v| *v > 0.0);
+    let volume_h = if has_volume && area.height >= 10 { (area.height / 5).clamp(2, 5) } else { 0 };
+    // Indicator panels get a caption row plus a few rows of chart, but
+    // only while the price plot keeps at least six rows
+    let panel_h = (area.height / 6).clamp(5, 7);
+    let panels = [shown.rsi, shown.macd].iter().filter(|on| **on).count() as u16;
+    let panel_h = if area.height >= 8 + volume_h + panels * panel_h { panel_h } else { 0 };
+    let rsi_h = if shown.rsi { panel_h } else { 0 };
+    let macd_h = if shown.macd { panel_h } else { 0 };
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(label_w), Constraint::Min(10)])
+        .split(area);
+    let rows = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(1), // current price
-            Constraint::Length(1), // direction
-            Constraint::Length(1), // input
+            Constraint::Min(2),
+            Constraint::Length(volume_h),
+            Constraint::Length(rsi_h),
+            Constraint::Length(macd_h),
+            Constraint::Length(1),
+        ])
+        .split(cols[1]);
+    let plot = rows[0];
+    // Nothing to draw into on a tiny terminal
+    if plot.width == 0 || plot.height == 0 {
+        return None;
+    }
+
+    // Price labels at top, middle and bottom of the plot
+    let label_rows = [plot.y, plot.y + plot.height / 2, plot.y + plot.height.saturating_sub(1)];
+    for (label, y) in y_labels.iter().zip(label_rows) {
+        f.render_widget(
+            Paragraph::new(format!("{:>w$} ", label, w = label_w as usize - 1))
+                .style(Style::default().fg(t.dim)),
+            Rect::new(cols[0].x, y, label_w, 1),
+        );
+    }
+    if volume_h > 0 {
+        f.render_widget(
+            Paragraph::new(format!("{:>w$} ", "vol", w = label_w as usize - 1))
+                .style(Style::default().fg(t.dim)),
+            Rect::new(cols[0].x, rows[1].y + rows[1].height - 1, label_w, 1),
+        );
+    }
+
+    draw_time_axis(f, app, rows[4], t0, t1);
+    let span = (t1 - t0).max(1);
+    let to_col = |ts: i64| -> u16 {
+        let frac = (ts - t0) as f64 / span as f64;
+        (frac.clamp(0.0, 1.0) * plot.width.saturating_sub(1) as f64).round() as u16
+    };
+    let cursor = app.chart_cursor.filter(|i| *i < history.prices.len());
+
+    if volume_h > 0 {
+        // Average volume per screen column, aligned with the plot
+        let mut sums = vec![(0.0, 0u32); plot.width as usize];
+        for (ts, v) in history.timestamps.iter().zip(&history.volumes) {
+            let slot = &mut sums[to_col(*ts) as usize];
+            slot.0 += v;
+            slot.1 += 1;
+        }
+        let avgs: Vec<f64> = sums.iter().map(|(s, n)| if *n > 0 { s / *n as f64 } else { 0.0 }).collect();
+        let vmin = avgs.iter().cloned().filter(|v| *v > 0.0).fold(f64::INFINITY, f64::min);
+        let vmax = avgs.iter().cloned().fold(0.0, f64::max);
+        // Scale from just under the smallest volume so changes stay visible
+        let base = if vmin.is_finite() { vmin * 0.9 } else { 0.0 };
+        let resolution = volume_h as f64 * 8.0;
+        let bars: Vec<u64> = avgs
+            .iter()
+            .map(|v| {
+                if *v <= 0.0 || vmax <= base {
+                    0
+                } else {
+                    (((v - base) / (vmax - base)) * resolution).max(1.0) as u64
+                }
+            })
+            .collect();
+        f.render_widget(
+            Sparkline::default()
+                .data(&bars)
+                .max(resolution as u64)
+                .style(Style::default().fg(t.dim)),
+            rows[1],
+        );
+    }
+
+    let plot_rows = plot.height as f64;
+    let to_row = |price: f64| -> u16 {
+        let frac = ((scale(price) - slo) / (shi - slo)).clamp(0.0, 1.0);
+        plot.y + ((1.0 - frac) * (plot_rows - 1.0)).round() as u16
+    };
+    let samples = plot.width as usize * 2;
+    let mut lines: Vec<(Vec<(f64, f64)>, Color)> = Vec::new();
+    if app.chart_ma != MovingAverage::Off {
+        let ma_colors = [t.accent, t.input_accent, t.fg];
+        for (&period, ma_color) in MovingAverage::PERIODS.iter().zip(ma_colors) {
+            let values = match app.chart_ma {
+                MovingAverage::Ema => indicators::ema(&history.prices, period),
+                _ => indicators::sma(&history.prices, period),
+            };
+            let points: Vec<(f64, f64)> = history
+                .timestamps
+                .iter()
+                .zip(values)
+                .filter_map(|(ts, v)| v.map(|v| (*ts as f64, scale(v))))
+                .collect();
+            if !points.is_empty() {
+                lines.push((downsample(&points, samples), ma_color));
+            }
+        }
+    }
+    if !bands.is_empty() {
+        for edge in [|b: &indicators::Band| b.upper, |b: &indicators::Band| b.lower] {
+            let points: Vec<(f64, f64)> = history
+                .timestamps
+                .iter()
+                .zip(&bands)
+                .filter_map(|(ts, b)| b.as_ref().map(|b| (*ts as f64, scale(edge(b)))))
+                .collect();
+            lines.push((downsample(&points, samples), t.dim));
+        }
+    }
+    // The price line goes last so it draws over the overlays
+    if candles.is_none() {
+        let points: Vec<(f64, f64)> = history
+            .timestamps
+            .iter()
+            .zip(&history.prices)
+            .map(|(ts, p)| (*ts as f64, scale(*p)))
+            .collect();
+        lines.push((downsample(&points, samples), color));
+    }
+    let datasets = lines
+        .iter()
+        .map(|(data, line_color)| {
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*line_color))
+                .data(data)
+        })
+        .collect();
+    let chart = Chart::new(datasets)
+        .x_axis(Axis::default().bounds([t0 as f64, t1.max(t0 + 1) as f64]))
+        .y_axis(Axis::default().bounds([slo, shi]));
+    f.render_widget(chart, plot);
+    if let Some(candles) = candles {
+        draw_candles(f, app, candles, plot, &to_row, &to_col);
+    }
+
+    // Buy price and price alert targets
+    if let Some(coin) = app.selected_coin() {
+        let mut levels: Vec<(f64, String, Color)> = app
+            .holdings
+            .iter()
+            .filter(|h| h.coin_id == coin.id && h.amount > 0.0)
+            .filter_map(|h| h.buy_price)
+            .map(|p| (p, format!("buy {}", format_price(p)), t.input_accent))
+            .collect();
+        levels.extend(
+            app.alerts
+                .iter()
+                .filter(|a| a.coin_id == coin.id && !a.triggered && a.kind == AlertKind::Price)
+                .map(|a| (a.target_price, format!("alert {}", alert_label(a)), t.accent)),
+        );
+        draw_levels(f.buffer_mut(), plot, (lo, hi), &levels, &to_row);
+    }
+
+    let x_bounds = [t0 as f64, t1.max(t0 + 1) as f64];
+    let at = |values: &[Option<f64>]| values.get(cursor.unwrap_or(values.len().saturating_sub(1))).copied().flatten();
+    if rsi_h > 0 {
+        let values = indicators::rsi(&history.prices, 14);
+        let caption = match at(&values) {
+            Some(v) => format!("RSI 14  {:.1}", v),
+            None => "RSI 14  not enough data".to_string(),
+        };
+        let series = [(series_points(&history.timestamps, &values), t.accent)];
+        let levels = [(70.0, "70".to_string(), t.negative), (30.0, "30".to_string(), t.positive)];
+        draw_indicator_panel(f, app, rows[2], x_bounds, (0.0, 100.0), &series, &levels, caption);
+    }
+    if macd_h > 0 {
+        let m = indicators::macd(&history.prices, 12, 26, 9);
+        let caption = match (at(&m.line), at(&m.signal)) {
+            (Some(l), Some(s)) => format!(
+                "MACD 12/26/9  {}  signal {}  hist {}",
+                format_signed(l),
+                format_signed(s),
+                format_signed(l - s)
+            ),
+            _ => "MACD 12/26/9  not enough data".to_string(),
+        };
+        let series = [
+            (series_points(&history.timestamps, &m.histogram), t.dim),
+            (series_points(&history.timestamps, &m.line), t.accent),
+            (series_points(&history.timestamps, &m.signal), t.input_accent),
+        ];
+        let (lo, hi) = series
+            .iter()
+            .flat_map(|(points, _)| points.iter().map(|p| p.1))
+            .fold((0.0_f64, 0.0_f64), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        let bounds = if hi > lo { (lo, hi) } else { (-1.0, 1.0) };
+        let levels = [(0.0, String::new(), t.dim)];
+        draw_indicator_panel(f, app, rows[3], x_bounds, bounds, &series, &levels, caption);
+    }
+
+    // Inspect cursor: tint the column under it down to the last panel
+    if let Some(i) = cursor {
+        let x = plot.x + to_col(history.timestamps[i]);
+        let buf = f.buffer_mut();
+        for y in plot.y..rows[4].y {
+            let cell = &mut buf[(x, y)];
+            if cell.symbol() == " " {
+                cell.set_symbol("\u{2502}").set_fg(t.dim);
+            } else {
+                cell.set_style(Style::default().add_modifier(Modifier::REVERSED));
+            }
+        }
+    }
+    Some(plot)
+}
+
+/// Candles placed by close time. Adjacent candles are merged when there are
+/// more than fit at two columns each, so bodies stay readable.
+fn draw_candles(
+    f: &mut Frame,
+    app: &App,
+    candles: &[Candle],
+    plot: Rect,
+    to_row: &dyn Fn(f64) -> u16,
+    to_col: &dyn Fn(i64) -> u16,
+) {
+    let t = &app.theme;
+    if candles.is_empty() || plot.width == 0 || plot.height == 0 {
+        return;
+    }
+    let slots = (plot.width as usize / 2).max(1);
+    let group = candles.len().div_ceil(slots);
+    let merged: Vec<Candle> = candles
+        .chunks(group)
+        .map(|c| Candle {
+            ts: c[c.len() - 1].ts,
+            open: c[0].open,
+            high: c.iter().map(|c| c.high).fold(f64::NEG_INFINITY, f64::max),
+            low: c.iter().map(|c| c.low).fold(f64::INFINITY, f64::min),
+            close: c[c.len() - 1].close,
+        })
+        .collect();
+    // Body width from the spacing between candles, leaving a gap
+    let spacing = plot.width as usize / merged.len().max(1);
+    let body_w = spacing.saturating_sub(1).clamp(1, 3) as u16;
+
+    let buf = f.buffer_mut();
+    for c in &merged {
+        let color = if c.close >= c.open { t.positive } else { t.negative };
+        let center = plot.x + to_col(c.ts);
+        let left = center.saturating_sub(body_w / 2).max(plot.x);
+        let right = (left + body_w).min(plot.x + plot.width);
+        let (body_top, body_bottom) = (to_row(c.open.max(c.close)), to_row(c.open.min(c.close)));
+        for y in to_row(c.high)..=to_row(c.low) {
+            if (body_top..=body_bottom).contains(&y) {
+                for x in left..right {
+                    buf[(x, y)].set_symbol("\u{2588}").set_fg(color);
+                }
+            } else {
+                buf[(center.min(plot.x + plot.width - 1), y)].set_symbol("\u{2502}").set_fg(color);
+            }
+        }
+    }
+}
+
+/// An indicator in a strip under the price chart: a caption row, then the
+/// series over the same time range as the plot. The first series is drawn
+/// as bars from zero, as for a MACD histogram, when there are several.
+#[allow(clippy::too_many_arguments)]
+fn draw_indicator_panel(
+    f: &mut Frame,
+    app: &App,
+    area: Rect,
+    x_bounds: [f64; 2],
+    (lo, hi): (f64, f64),
+    series: &[(Vec<(f64, f64)>, Color)],
+    levels: &[(f64, String, Color)],
+    caption: String,
+) {
+    if area.height < 2 {
+        return;
+    }
+    f.render_widget(
+        Paragraph::new(caption).style(Style::default().fg(app.theme.dim)),
+        Rect::new(area.x, area.y, area.width, 1),
+    );
+    let plot = Rect::new(area.x, area.y + 1, area.width, area.height - 1);
+    let sampled: Vec<(Vec<(f64, f64)>, Color)> = series
+        .iter()
+        .map(|(points, color)| (downsample(points, plot.width as usize * 2), *color))
+        .collect();
+    let bars = sampled.len() > 1;
+    let datasets = sampled
+        .iter()
+        .enumerate()
+        .map(|(i, (data, color))| {
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(if bars && i == 0 { GraphType::Bar } else { GraphType::Line })
+                .style(Style::default().fg(*color))
+                .data(data)
+        })
+        .collect();
+    let chart = Chart::new(datasets)
+        .x_axis(Axis::default().bounds(x_bounds))
+        .y_axis(Axis::default().bounds([lo, hi]));
+    f.render_widget(chart, plot);
+
+    let rows = plot.height as f64;
+    let to_row = |v: f64| -> u16 {
+        let frac = ((v - lo) / (hi - lo)).clamp(0.0, 1.0);
+        plot.y + ((1.0 - frac) * (rows - 1.0)).round() as u16
+    };
+    draw_levels(f.buffer_mut(), plot, (lo, hi), levels, &to_row);
+}
+
+/// `(ts, value)` pairs for the samples where an indicator is defined.
+fn series_points(timestamps: &[i64], values: &[Option<f64>]) -> Vec<(f64, f64)> {
+    timestamps
+        .iter()
+        .zip(values)
+        .filter_map(|(ts, v)| v.map(|v| (*ts as f64, v)))
+        .collect()
+}
+
+fn format_signed(v: f64) -> String {
+    if v < 0.0 {
+        format!("-{}", format_price(-v))
+    } else {
+        format!("+{}", format_price(v))
+    }
+}
+
+/// Time labels for `t0..t1`, spread evenly and clipped to the axis row.
+fn draw_time_axis(f: &mut Frame, app: &App, axis: Rect, t0: i64, t1: i64) {
+    let span = (t1 - t0).max(1);
+    let fmt = if span <= 2 * 86400 { "%H:%M" } else if span <= 200 * 86400 { "%b %d" } else { "%b %Y" };
+    let ticks = (axis.width / 14).clamp(2, 6);
+    for i in 0..ticks {
+        let frac = i as f64 / (ticks - 1) as f64;
+        let label = format_time(t0 + (span as f64 * frac) as i64, fmt);
+        let col = (frac * axis.width.saturating_sub(1) as f64) as u16;
+        let w = label.len() as u16;
+        let x = (axis.x + col).saturating_sub(w / 2).clamp(axis.x, (axis.x + axis.width).saturating_sub(w));
+        f.render_widget(
+            Paragraph::new(label).style(Style::default().fg(app.theme.dim)),
+            Rect::new(x, axis.y, w.min(axis.width), 1),
+        );
+    }
+}
+
+/// Dashed lines at fixed prices, labelled at the left edge. Levels outside
+/// the visible range are listed against the top or bottom edge instead.
+fn draw_levels(
+    buf: &mut Buffer,
+    plot: Rect,
+    (lo, hi): (f64, f64),
+    levels: &[(f64, String, Color)],
+    to_row: &dyn Fn(f64) -> u16,
+) {
+    let (mut above, mut below) = (0, 0);
+    for (price, label, color) in levels {
+        let (y, label) = if *price > hi {
+            above += 1;
+            (plot.y + above - 1, format!("\u{2191} {}", label))
+        } else if *price < lo {
+            below += 1;
+            ((plot.y + plot.height).saturating_sub(below), format!("\u{2193} {}", label))
+        } else {
+            let y = to_row(*price);
+            for x in plot.x..plot.x + plot.width {
+                let cell = &mut buf[(x, y)];
+                if matches!(cell.symbol(), " " | "\u{2800}") {
+                    cell.set_symbol("\u{254c}").set_fg(*color);
+                }
+            }
+            (y, label.clone())
+        };
+        if y < plot.y || above + below > plot.height {
+            continue;
+        }
+        if !label.is_empty() {
+            buf.set_stringn(plot.x, y, format!(" {} ", label), plot.width as usize, Style::default().fg(*color));
+        }
+    }
+}
+
+fn format_time(ts: i64, fmt: &str) -> String {
+    use chrono::TimeZone;
+    chrono::Local
+        .timestamp_opt(ts, 0)
+        .single()
+        .map(|d| d.format(fmt).to_string())
+        .unwrap_or_default()
+}
+
+// -- Compare popup --
+
+/// Marked coins on one chart, each rebased to 0% at the start of the range.
+fn draw_compare(f: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let area = centered_rect(75, 65, f.area());
+    f.render_widget(Clear, area);
+
+    let log = app.chart_log_scale();
+    let title = format!(" Compare - {}{} ", app.chart_view.label(), if log { " log" } else { "" });
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.accent));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Log mode plots ln(price / start), so equal moves look equal at any size
+    let rebase = |p: f64, p0: f64| if log { (p / p0).ln() } else { (p / p0 - 1.0) * 100.0 };
+    let pct = |v: f64| if log { (v.exp() - 1.0) * 100.0 } else { v };
+    let palette = [t.accent, t.input_accent, t.positive, t.negative, t.fg, t.dim];
+    let days = app.chart_view.days();
+
+    let mut legend = Vec::new();
+    let mut series: Vec<(Vec<(f64, f64)>, Color)> = Vec::new();
+    for (i, id) in app.compare_ids.iter().enumerate() {
+        let color = palette[i % palette.len()];
+        let symbol = app
+            .coins
+            .iter()
+            .find(|c| c.id == *id)
+            .map(|c| c.symbol.to_uppercase())
+            .unwrap_or_else(|| id.clone());
+        let history = app.chart_cache.get(&(id.clone(), days));
+        let p0 = history.and_then(|h| h.prices.first().copied()).filter(|p| *p > 0.0);
+        let (Some(history), Some(p0)) = (history, p0) else {
+            legend.push(Span::styled(format!(" \u{25a0} {} loading ", symbol), Style::default().fg(t.dim)));
+            continue;
+        };
+        let points: Vec<(f64, f64)> = history
+            .timestamps
+            .iter()
+            .zip(&history.prices)
+            .map(|(ts, p)| (*ts as f64, rebase(*p, p0)))
+            .collect();
+        let change = points.last().map(|p| pct(p.1)).unwrap_or(0.0);
+        legend.push(Span::styled(format!(" \u{25a0} {} {:+.2}% ", symbol, change), Style::default().fg(color)));
+        series.push((points, color));
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(3)])
+        .split(inner);
+    f.render_widget(
+        Paragraph::new(Line::from(legend)).wrap(ratatui::widgets::Wrap { trim: true }),
+        chunks[0],
+    );
+    if series.is_empty() {
+        return;
+    }
+
+    let all = || series.iter().flat_map(|(points, _)| points.iter());
+    let t0 = all().map(|p| p.0).fold(f64::INFINITY, f64::min) as i64;
+    let t1 = all().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max) as i64;
+    let min = all().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max = all().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    let (lo, hi) = if max > min { (min, max) } else { (min - 1.0, max + 1.0) };
+
+    let y_labels = [format!("{:+.1}%", pct(hi)), format!("{:+.1}%", pct(lo))];
+    let label_w = y_labels.iter().map(|l| l.len()).max().unwrap_or(0) as u16 + 2;
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(label_w), Constraint::Min(10)])
+        .split(chunks[1]);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(2), Constraint::Length(1)])
+        .split(cols[1]);
+    let plot = rows[0];
+    for (label, y) in y_labels.iter().zip([plot.y, plot.y + plot.height.saturating_sub(1)]) {
+        f.render_widget(
+            Paragraph::new(format!("{:>w$} ", label, w = label_w as usize - 1))
+                .style(Style::default().fg(t.dim)),
+            Rect::new(cols[0].x, y, label_w, 1),
+        );
+    }
+    draw_time_axis(f, app, rows[1], t0, t1);
+
+    let sampled: Vec<(Vec<(f64, f64)>, Color)> = series
+        .iter()
+        .map(|(points, color)| (downsample(points, plot.width as usize * 2), *color))
+        .collect();
+    let datasets = sampled
+        .iter()
+        .map(|(data, color)| {
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(*color))
+                .data(data)
+        })
+        .collect();
+    let chart = Chart::new(datasets)
+        .x_axis(Axis::default().bounds([t0 as f64, t1.max(t0 + 1) as f64]))
+        .y_axis(Axis::default().bounds([lo, hi]));
+    f.render_widget(chart, plot);
+
+    // Starting line
+    let plot_rows = plot.height as f64;
+    let to_row = |v: f64| -> u16 {
+        let frac = ((v - lo) / (hi - lo)).clamp(0.0, 1.0);
+        plot.y + ((1.0 - frac) * (plot_rows - 1.0)).round() as u16
+    };
+    draw_levels(f.buffer_mut(), plot, (lo, hi), &[(0.0, "0%".to_string(), t.dim)], &to_row);
+}
+
+// -- Amount input popup --
+
+fn draw_input_popup(f: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let coin = match app.selected_coin() {
+        Some(c) => c,
+        None => return,
+    };
+
+    let area = centered_rect(40, 5, f.area());
+    let area = Rect {
+        height: area.height.max(5),
+        ..area
+    };
+    f.render_widget(Clear, area);
+
+    let title = format!(" {} amount ", coin.symbol.to_uppercase());
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.input_accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let text = if app.input_buf.is_empty() {
+        "Enter amount (0 to remove): _"
+    } else {
+        &app.input_buf
+    };
+
+    let input = Paragraph::new(format!(" {}_", text.trim_end_matches('_')))
+        .style(Style::default().fg(t.fg));
+    f.render_widget(input, inner);
+}
+
+// -- Alert popup --
+
+fn draw_alert_popup(f: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let coin = match app.selected_coin() {
+        Some(c) => c,
+        None => return,
+    };
+
+    let area = centered_rect(45, 5, f.area());
+    let area = Rect { height: area.height.max(12), width: area.width.max(50).min(f.area().width), ..area };
+    f.render_widget(Clear, area);
+
+    let title = format!(" {} alert ", coin.symbol.to_uppercase());
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.input_accent));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // current value
+            Constraint::Length(1), // kind
+            Constraint::Length(1), // direction
+            Constraint::Length(1), // input
+            Constraint::Length(1), // notify label
+            Constraint::Length(1), // sinks
+            Constraint::Length(1), // ntfy priority
+            Constraint::Length(1), // ntfy tags
+            Constraint::Length(1), // ntfy click URL
             Constraint::Length(1), // hint
             Constraint::Min(0),
         ])
         .split(inner);
-
-    let price_line = format!(" Current: {}", format_price(coin.current_price));
+    let marker = |field: AlertField| if app.alert_field == field { "\u{25b8}" } else { " " };
+
+    let current_line = match app.alert_kind {
+        AlertKind::Price => format!(" Current: {}", format_price(coin.current_price)),
+        AlertKind::VolumeSpike => format!(" Volume: {}", format_large(coin.total_volume)),
+        AlertKind::Rank => format!(
+            " Rank: {}",
+            coin.market_cap_rank.map(|r| format!("#{}", r)).unwrap_or_else(|| "--".into())
+        ),
+    };
     f.render_widget(
-        Paragraph::new(price_line).style(Style::default().fg(t.dim)),
+        Paragraph::new(current_line).style(Style::default().fg(t.dim)),
         chunks[0],
     );
 
-    let dir_label = match app.alert_direction {
-        AlertDirection::Above => "\u{25b2} Above",
-        AlertDirection::Below => "\u{25bc} Below",
-    };
     f.render_widget(
-        Paragraph::new(format!(" Direction: {} (Tab to toggle)", dir_label))
+        Paragraph::new(format!(" Type: \u{25c2} {} \u{25b8}", app.alert_kind.label()))
             .style(Style::default().fg(t.accent)),
         chunks[1],
     );
 
-    let input_text = if app.alert_input_buf.is_empty() {
-        " Target: _".to_string()
-    } else {
-        format!(" Target: {}_", app.alert_input_buf)
+    let dir_label = match (app.alert_kind, app.alert_direction) {
+        (AlertKind::Price, AlertDirection::Above) => " Direction: \u{25b2} Above (Tab to toggle)",
+        (AlertKind::Price, AlertDirection::Below) => " Direction: \u{25bc} Below (Tab to toggle)",
+        (AlertKind::VolumeSpike, _) => " Fires when volume \u{2265} N\u{d7} its 24h average",
+        (AlertKind::Rank, AlertDirection::Above) => " Direction: \u{25b2} Enters top N (Tab to toggle)",
+        (AlertKind::Rank, AlertDirection::Below) => " Direction: \u{25bc} Leaves top N (Tab to toggle)",
     };
     f.render_widget(
-        Paragraph::new(input_text).style(Style::default().fg(t.fg)),
+        Paragraph::new(dir_label).style(Style::default().fg(t.accent)),
         chunks[2],
     );
 
+    let input_label = match app.alert_kind {
+        AlertKind::Price => "Target",
+        AlertKind::VolumeSpike => "Multiplier",
+        AlertKind::Rank => "Top N",
+    };
+    let cursor = if app.alert_field == AlertField::Target { "_" } else { "" };
+    let input_text = format!("{}{}: {}{}", marker(AlertField::Target), input_label, app.alert_input_buf, cursor);
     f.render_widget(
-        Paragraph::new(" Enter save | Esc cancel").style(Style::default().fg(t.dim)),
+        Paragraph::new(input_text).style(Style::default().fg(t.fg)),
         chunks[3],
     );
+
+    let notify_label = if app.alert_sinks.is_empty() {
+        "Notify: enabled sinks (\u{2190}/\u{2192} + Space to pick)"
+    } else {
+        "Notify: only these (\u{2190}/\u{2192} + Space to pick)"
+    };
+    f.render_widget(
+        Paragraph::new(format!("{}{}", marker(AlertField::Sinks), notify_label))
+            .style(Style::default().fg(t.accent)),
+        chunks[4],
+    );
+    let sink_cursor = (app.alert_field == AlertField::Sinks).then_some(app.alert_sink_idx);
+    let spans = sink_spans(app, chunks[5].width as usize, sink_cursor, |name| {
+        app.alert_sinks.iter().any(|s| s == name)
+    });
+    f.render_widget(Paragraph::new(Line::from(spans)), chunks[5]);
+
+    let ntfy = &app.alert_ntfy;
+    let ntfy_rows = [
+        (AlertField::NtfyPriority, "ntfy priority", ntfy.priority.as_str(), chunks[6]),
+        (AlertField::NtfyTags, "ntfy tags", ntfy.tags.as_str(), chunks[7]),
+        (AlertField::NtfyClickUrl, "ntfy click URL", ntfy.click_url.as_str(), chunks[8]),
+    ];
+    for (field, label, value, row) in ntfy_rows {
+        let focused = app.alert_field == field;
+        let editing = focused && field != AlertField::NtfyPriority;
+        let shown = if value.is_empty() && !editing { "from settings" } else { value };
+        let value = match (focused, field) {
+            (true, AlertField::NtfyPriority) => format!("\u{25c2} {} \u{25b8}", shown),
+            (true, _) => format!("{}_", shown),
+            _ => shown.to_string(),
+        };
+        let style = if focused { Style::default().fg(t.fg) } else { Style::default().fg(t.dim) };
+        f.render_widget(
+            Paragraph::new(format!("{}{}: {}", marker(field), label, value)).style(style),
+            row,
+        );
+    }
+
+    f.render_widget(
+        Paragraph::new(" \u{2191}/\u{2193} row | \u{2190}/\u{2192} change | Enter save | Esc cancel")
+            .style(Style::default().fg(t.dim)),
+        chunks[9],
+    );
 }
 
 // -- Buy price popup --
@@ -753,7 +1955,7 @@ fn draw_buyprice_popup(f: &mut Frame, app: &App) {
 fn draw_settings(f: &mut Frame, app: &App) {
     let t = &app.theme;
     let area = f.area();
-    let box_w = 60_u16.min(area.width.saturating_sub(4));
+    let box_w = 72_u16.min(area.width.saturating_sub(4));
     let box_h = 26_u16.min(area.height.saturating_sub(2));
     let x = (area.width.saturating_sub(box_w)) / 2;
     let y = (area.height.saturating_sub(box_h)) / 2;
@@ -772,83 +1974,186 @@ fn draw_settings(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(1), // [0] blank
-            Constraint::Length(1), // [1] currency label
-            Constraint::Length(1), // [2] currency value
-            Constraint::Length(1), // [3] blank
-            Constraint::Length(1), // [4] theme label
-            Constraint::Length(1), // [5] theme value
-            Constraint::Length(1), // [6] blank
-            Constraint::Length(1), // [7] coingecko label
-            Constraint::Length(1), // [8] coingecko value
-            Constraint::Length(1), // [9] blank
-            Constraint::Length(1), // [10] cmc label
-            Constraint::Length(1), // [11] cmc value
-            Constraint::Length(1), // [12] blank
-            Constraint::Length(1), // [13] notifications label
-            Constraint::Length(1), // [14] notifications value
-            Constraint::Length(1), // [15] blank
-            Constraint::Length(1), // [16] ntfy topic label
-            Constraint::Length(1), // [17] ntfy topic value
-            Constraint::Length(1), // [18] blank
-            Constraint::Length(1), // [19] hint
-            Constraint::Min(0),
+            Constraint::Min(3),    // fields
+            Constraint::Length(1), // hint
         ])
         .split(inner);
 
-    // -- Currency field --
-    draw_cycle_field(f, t, chunks[1], chunks[2],
-        app.settings_field == SettingsField::Currency,
-        "Currency",
-        &format!("{} ({})", CURRENCIES[app.settings_currency_idx].to_uppercase(), currency_symbol(CURRENCIES[app.settings_currency_idx])),
-    );
+    // Each field takes a blank, a label and a value row; scroll to keep the
+    // selected one in view.
+    let fields = SettingsField::ALL;
+    let per_page = (chunks[0].height / 3).max(1) as usize;
+    let selected = app.settings_field.index();
+    let first = selected.saturating_sub(per_page - 1);
+
+    for (slot, field) in fields.iter().skip(first).take(per_page).enumerate() {
+        let row_y = chunks[0].y + slot as u16 * 3 + 1;
+        let label_area = Rect::new(chunks[0].x, row_y, chunks[0].width, 1);
+        let value_area = Rect::new(chunks[0].x, row_y + 1, chunks[0].width, 1);
+        let is_selected = app.settings_field == *field;
+        let n = &app.settings_notifications;
+
+        match field {
+            SettingsField::Currency => draw_cycle_field(f, t, label_area, value_area,
+                is_selected,
+                field.label(),
+                &format!("{} ({})", CURRENCIES[app.settings_currency_idx].to_uppercase(), currency_symbol(CURRENCIES[app.settings_currency_idx])),
+            ),
+            SettingsField::Theme => draw_cycle_field(f, t, label_area, value_area,
+                is_selected,
+                field.label(),
+                THEME_NAMES[app.settings_theme_idx],
+            ),
+            SettingsField::MarketsLimit => draw_cycle_field(f, t, label_area, value_area,
+                is_selected,
+                field.label(),
+                &format!("top {}", MARKET_LIMITS[app.settings_markets_idx]),
+            ),
+            SettingsField::LazyLoad => draw_cycle_field(f, t, label_area, value_area,
+                is_selected,
+                field.label(),
+                if app.settings_lazy_load { "on" } else { "off" },
+            ),
+            SettingsField::Notifications => draw_sinks_field(f, app, label_area, value_area, is_selected),
+            SettingsField::TestNotification => draw_test_field(f, app, label_area, value_area, is_selected),
+            SettingsField::NtfyPriority => draw_cycle_field(f, t, label_area, value_area,
+                is_selected,
+                field.label(),
+                crate::notifications::NTFY_PRIORITIES[n.ntfy.priority_idx()],
+            ),
+            SettingsField::WebhookMethod => draw_cycle_field(f, t, label_area, value_area,
+                is_selected,
+                field.label(),
+                crate::notifications::WEBHOOK_METHODS[n.webhook.method_idx()],
+            ),
+            SettingsField::SmtpSecurity => draw_cycle_field(f, t, label_area, value_area,
+                is_selected,
+                field.label(),
+                crate::notifications::SMTP_SECURITY[n.smtp.security_idx()],
+            ),
+            SettingsField::QuietMode => draw_cycle_field(f, t, label_area, value_area,
+                is_selected,
+                field.label(),
+                match crate::notifications::QUIET_MODES[n.quiet.mode_idx()] {
+                    "drop" => "drop (bell/flash only)",
+                    _ => "hold until end",
+                },
+            ),
+            _ => draw_text_field(f, t, label_area, value_area,
+                is_selected, app.settings_editing && is_selected,
+                field.label(), app.settings_text_value(*field), field.is_masked(),
+            ),
+        }
+    }
+
+    let placeholders;
+    let hint = if app.settings_editing && app.settings_field.is_template_field() {
+        placeholders = format!("  {} | Enter/Esc finish", crate::notifications::TEMPLATE_PLACEHOLDERS);
+        &placeholders
+    } else if app.settings_editing {
+        "  Enter/Esc finish editing"
+    } else if app.settings_field == SettingsField::Notifications {
+        "  h/l pick | Space toggle | s save & close | Esc cancel"
+    } else if app.settings_field == SettingsField::TestNotification {
+        "  Enter send test (unsaved settings) | s save & close | Esc cancel"
+    } else if app.settings_field.is_cycle_field() {
+        "  h/l change | s save & close | Esc cancel"
+    } else {
+        "  Enter edit | s save & close | Esc cancel"
+    };
+    let hint_p = Paragraph::new(hint)
+        .style(Style::default().fg(t.dim));
+    f.render_widget(hint_p, chunks[1]);
+}
 
-    // -- Theme field --
-    draw_cycle_field(f, t, chunks[4], chunks[5],
-        app.settings_field == SettingsField::Theme,
-        "Theme",
-        THEME_NAMES[app.settings_theme_idx],
+fn draw_sinks_field(f: &mut Frame, app: &App, label_area: Rect, value_area: Rect, is_selected: bool) {
+    let t = &app.theme;
+    let label_style = if is_selected {
+        Style::default().fg(t.fg).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(t.dim)
+    };
+    let marker = if is_selected { "\u{25b8} " } else { "  " };
+    f.render_widget(
+        Paragraph::new(format!("{}{}", marker, SettingsField::Notifications.label())).style(label_style),
+        label_area,
     );
 
-    // -- API key fields --
-    let api_fields: [(SettingsField, &String, usize, usize); 2] = [
-        (SettingsField::CoingeckoApiKey, &app.settings_coingecko_key, 7, 8),
-        (SettingsField::CoinmarketcapApiKey, &app.settings_cmc_key, 10, 11),
-    ];
+    let spans = sink_spans(
+        app,
+        value_area.width as usize,
+        is_selected.then_some(app.settings_sink_idx),
+        |name| app.settings_notifications.is_enabled(name),
+    );
+    f.render_widget(Paragraph::new(Line::from(spans)), value_area);
+}
 
-    for (field, value, label_row, value_row) in &api_fields {
-        draw_text_field(f, t, chunks[*label_row], chunks[*value_row],
-            app.settings_field == *field, app.settings_editing && app.settings_field == *field,
-            field.label(), value, true,
-        );
+/// One `●`/`○` entry per sink, sliding so `cursor` stays visible on narrow areas.
+fn sink_spans<'a>(app: &App, width: usize, cursor: Option<usize>, is_on: impl Fn(&str) -> bool) -> Vec<Span<'a>> {
+    let t = &app.theme;
+    let avail = width.saturating_sub(8);
+    let width = |i: usize| NOTIFICATION_SINKS[i].len() + 4;
+    let focus = cursor.unwrap_or(0);
+    let mut first = 0;
+    while first < focus && (first..=focus).map(width).sum::<usize>() > avail {
+        first += 1;
     }
 
-    // -- Notification method --
-    draw_cycle_field(f, t, chunks[13], chunks[14],
-        app.settings_field == SettingsField::Notifications,
-        "Notifications",
-        NOTIFICATION_METHODS[app.settings_notification_idx],
-    );
+    let mut spans = vec![Span::styled(
+        if first > 0 { "  \u{2026} " } else { "    " },
+        Style::default().fg(t.dim),
+    )];
+    let mut used = 0;
+    for (i, name) in NOTIFICATION_SINKS.iter().enumerate().skip(first) {
+        if used + width(i) > avail {
+            spans.push(Span::styled("\u{2026}", Style::default().fg(t.dim)));
+            break;
+        }
+        used += width(i);
+        let enabled = is_on(name);
+        let check = if enabled { "\u{25cf} " } else { "\u{25cb} " };
+        let mut style = if enabled {
+            Style::default().fg(t.accent)
+        } else {
+            Style::default().fg(t.dim)
+        };
+        if cursor == Some(i) {
+            style = style.fg(t.fg).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        }
+        spans.push(Span::styled(format!("{}{}", check, name), style));
+        spans.push(Span::raw("  "));
+    }
+    spans
+}
 
-    // -- Ntfy topic --
-    draw_text_field(f, t, chunks[16], chunks[17],
-        app.settings_field == SettingsField::NtfyTopic,
-        app.settings_editing && app.settings_field == SettingsField::NtfyTopic,
-        "Ntfy Topic",
-        &app.settings_ntfy_topic,
-        false,
+fn draw_test_field(f: &mut Frame, app: &App, label_area: Rect, value_area: Rect, is_selected: bool) {
+    let t = &app.theme;
+    let label_style = if is_selected {
+        Style::default().fg(t.fg).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(t.dim)
+    };
+    let marker = if is_selected { "\u{25b8} " } else { "  " };
+    f.render_widget(
+        Paragraph::new(format!("{}{}", marker, SettingsField::TestNotification.label())).style(label_style),
+        label_area,
     );
 
-    let hint = if app.settings_editing {
-        "  Enter/Esc finish editing"
-    } else if app.settings_field.is_cycle_field() {
-        "  h/l change | s save & close | Esc cancel"
+    let line = if app.notification_test_pending {
+        Line::from(Span::styled("    Sending...", Style::default().fg(t.dim)))
+    } else if app.notification_test_results.is_empty() {
+        Line::from(Span::styled("    Press Enter to send", Style::default().fg(t.dim)))
     } else {
-        "  Enter edit | s save & close | Esc cancel"
+        let mut spans = vec![Span::raw("    ")];
+        for (sink, err) in &app.notification_test_results {
+            match err {
+                None => spans.push(Span::styled(format!("{} \u{2713}  ", sink), Style::default().fg(t.positive))),
+                Some(e) => spans.push(Span::styled(format!("{} \u{2717} {}  ", sink, e), Style::default().fg(t.error))),
+            }
+        }
+        Line::from(spans)
     };
-    let hint_p = Paragraph::new(hint)
-        .style(Style::default().fg(t.dim));
-    f.render_widget(hint_p, chunks[19]);
+    f.render_widget(Paragraph::new(line), value_area);
 }
 
 fn draw_cycle_field(f: &mut Frame, t: &crate::theme::Theme, label_area: Rect, value_area: Rect, is_selected: bool, label: &str, value: &str) {
@@ -872,6 +2177,7 @@ fn draw_cycle_field(f: &mut Frame, t: &crate::theme::Theme, label_area: Rect, va
     f.render_widget(Paragraph::new(val_spans), value_area);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_text_field(f: &mut Frame, t: &crate::theme::Theme, label_area: Rect, value_area: Rect, is_selected: bool, is_editing: bool, label: &str, value: &str, mask: bool) {
     let label_style = if is_selected {
         Style::default().fg(t.fg).add_modifier(Modifier::BOLD)
@@ -1037,6 +2343,164 @@ fn draw_search(f: &mut Frame, app: &App) {
     }
 }
 
+// -- Sector overview --
+
+fn draw_sectors(f: &mut Frame, app: &App) {
+    let t = &app.theme;
+    let area = f.area();
+    let box_w = 96_u16.min(area.width.saturating_sub(4));
+    let box_h = area.height.saturating_sub(4).max(8).min(area.height);
+    let popup = Rect::new(
+        (area.width.saturating_sub(box_w)) / 2,
+        (area.height.saturating_sub(box_h)) / 2,
+        box_w,
+        box_h,
+    );
+
+    f.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .title(" Sectors ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.accent));
+
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // filter + sort
+            Constraint::Length(1), // blank
+            Constraint::Min(2),    // table
+        ])
+        .split(inner);
+
+    let filter = format!("  Filter: {}_", app.sector_query);
+    let sort = format!("sorted by {} ", app.sector_sort.label());
+    let pad = (chunks[0].width as usize).saturating_sub(filter.chars().count() + sort.chars().count());
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(filter, Style::default().fg(t.input_accent)),
+            Span::raw(" ".repeat(pad)),
+            Span::styled(sort, Style::default().fg(t.dim)),
+        ])),
+        chunks[0],
+    );
+
+    let sectors = app.visible_sectors();
+    if sectors.is_empty() && app.sector_query.is_empty() {
+        let p = Paragraph::new("  Loading sectors...").style(Style::default().fg(t.dim));
+        f.render_widget(p, chunks[2]);
+        return;
+    }
+
+    let total = app.global_stats.as_ref().map(|s| s.total_market_cap_usd).filter(|v| *v > 0.0);
+    let symbol_of = |id: &str| {
+        app.coins
+            .iter()
+            .find(|c| c.id == id)
+            .map(|c| c.symbol.to_uppercase())
+            .unwrap_or_else(|| id.to_string())
+    };
+
+    let header = Row::new(
+        ["Sector", "MCap(USD)", "Share", "24h%", "Volume", "Leaders"]
+            .into_iter()
+            .map(|h| Cell::from(h).style(Style::default().fg(t.dim))),
+    );
+
+    // Row 0 resets to all coins; keep the selection in view
+    let page = chunks[2].height.saturating_sub(1).max(1) as usize;
+    let first = app.sector_selected.saturating_sub(page - 1);
+
+    let all = Row::new(vec![
+        Cell::from("All coins").style(Style::default().fg(t.fg)),
+        Cell::from(total.map(format_large).unwrap_or_else(|| "--".into())).style(Style::default().fg(t.dim)),
+        Cell::from(if total.is_some() { "100%" } else { "--" }).style(Style::default().fg(t.dim)),
+        Cell::from(""),
+        Cell::from(""),
+        Cell::from(""),
+    ]);
+    let rows: Vec<Row> = std::iter::once(all)
+        .chain(sectors.iter().map(|c| {
+            let active = app.market_category.as_ref().is_some_and(|m| m.id == c.id);
+            let name_style = if active {
+                Style::default().fg(t.accent).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(t.fg)
+            };
+            let change = format_pct(c.market_cap_change_24h);
+            let share = match (c.market_cap, total) {
+                (Some(m), Some(total)) => format!("{:.1}%", m / total * 100.0),
+                _ => "--".to_string(),
+            };
+            let leaders = c
+                .top_3_coins_id
+                .as_deref()
+                .unwrap_or_default()
+                .iter()
+                .map(|id| symbol_of(id))
+                .collect::<Vec<_>>()
+                .join(" ");
+            Row::new(vec![
+                Cell::from(c.name.clone()).style(name_style),
+                Cell::from(c.market_cap.map(format_large).unwrap_or_else(|| "--".into())).style(Style::default().fg(t.dim)),
+                Cell::from(share).style(Style::default().fg(t.dim)),
+                pct_cell(c.market_cap_change_24h, &change, t.positive, t.negative, t.dim),
+                Cell::from(c.volume_24h.map(format_large).unwrap_or_else(|| "--".into())).style(Style::default().fg(t.dim)),
+                Cell::from(leaders).style(Style::default().fg(t.accent)),
+            ])
+        }))
+        .enumerate()
+        .skip(first)
+        .take(page)
+        .map(|(i, row)| {
+            if i == app.sector_selected {
+                row.style(Style::default().bg(t.highlight_bg).fg(t.highlight_fg))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(30),
+        Constraint::Length(10),
+        Constraint::Length(6),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Min(10),
+    ];
+    let table = Table::new(rows, widths).header(header).column_spacing(1);
+    f.render_widget(table, Rect { x: chunks[2].x + 2, width: chunks[2].width.saturating_sub(2), ..chunks[2] });
+}
+
+fn alert_label(alert: &PriceAlert) -> String {
+    let dir = match alert.direction {
+        AlertDirection::Above => "\u{25b2}",
+        AlertDirection::Below => "\u{25bc}",
+    };
+    match alert.kind {
+        AlertKind::Price => format!("{}{}", dir, format_price(alert.target_price)),
+        AlertKind::VolumeSpike => format!("vol\u{2265}{}x", alert.target_price),
+        AlertKind::Rank => format!("{}top{}", dir, alert.target_price as u32),
+    }
+}
+
+/// "stale since 14:05", with the date when the data is from another day.
+fn stale_label(ts: i64) -> String {
+    use chrono::TimeZone;
+    let Some(at) = chrono::Local.timestamp_opt(ts, 0).single() else {
+        return "stale".to_string();
+    };
+    if at.date_naive() == chrono::Local::now().date_naive() {
+        format!("stale since {}", at.format("%H:%M"))
+    } else {
+        format!("stale since {}", at.format("%b %-d %H:%M"))
+    }
+}
+
 fn mask_key(key: &str) -> String {
     if key.len() <= 6 {
         "\u{2022}".repeat(key.len())
@@ -1057,7 +2521,7 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     Rect::new(r.x + x, r.y + y, popup_width, popup_height)
 }
 
-fn format_price(v: f64) -> String {
+pub(crate) fn format_price(v: f64) -> String {
     if v >= 1.0 {
         let s = format!("{:.2}", v);
         add_commas(&s)
@@ -1115,40 +2579,47 @@ fn pct_cell(v: Option<f64>, formatted: &str, positive: Color, negative: Color, d
     Cell::from(formatted.to_string()).style(Style::default().fg(color))
 }
 
-fn downsample(data: &[f64], target_len: usize) -> Vec<f64> {
+/// Reduce `(x, y)` points to about `target_len`. Each bucket keeps both its
+/// lowest and highest point, in time order, so spikes and dips survive at any
+/// range length.
+fn downsample(data: &[(f64, f64)], target_len: usize) -> Vec<(f64, f64)> {
     if target_len == 0 || data.is_empty() {
         return vec![];
     }
     if data.len() <= target_len {
         return data.to_vec();
     }
-    let mut result = Vec::with_capacity(target_len);
-    let bucket_size = data.len() as f64 / target_len as f64;
-    for i in 0..target_len {
+    let buckets = (target_len / 2).max(1);
+    let bucket_size = data.len() as f64 / buckets as f64;
+    let mut result: Vec<(f64, f64)> = Vec::with_capacity(target_len + 2);
+    result.push(data[0]);
+    for i in 0..buckets {
         let start = (i as f64 * bucket_size) as usize;
         let end = (((i + 1) as f64 * bucket_size) as usize).min(data.len());
         if start >= end {
-            if let Some(&last) = result.last() {
-                result.push(last);
-            }
             continue;
         }
-        // Use min-max-close to preserve peaks and valleys
         let slice = &data[start..end];
-        let min = slice.iter().cloned().fold(f64::INFINITY, f64::min);
-        let max = slice.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-        let close = slice[slice.len() - 1];
-        // Pick whichever extreme is further from the previous point to preserve shape
-        if let Some(&prev) = result.last() {
-            let d_min = f64::abs(min - prev);
-            let d_max = f64::abs(max - prev);
-            if d_min > d_max {
-                result.push(min);
-            } else {
-                result.push(max);
+        let (mut min, mut max) = (slice[0], slice[0]);
+        for &p in slice {
+            if p.1 < min.1 {
+                min = p;
+            }
+            if p.1 > max.1 {
+                max = p;
             }
-        } else {
-            result.push(close);
+        }
+        let (a, b) = if min.0 <= max.0 { (min, max) } else { (max, min) };
+        for p in [a, b] {
+            if result.last().is_none_or(|last| p.0 > last.0) {
+                result.push(p);
+            }
+        }
+    }
+    // Always end on the latest price
+    if let (Some(&last), Some(&kept)) = (data.last(), result.last()) {
+        if last.0 > kept.0 {
+            result.push(last);
         }
     }
     result
@@ -1171,3 +2642,96 @@ fn add_commas(s: &str) -> String {
         int_formatted
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every block lies inside `area` and no two blocks share a cell.
+    fn assert_tiles(weights: &[f64], area: Rect) -> Vec<Rect> {
+        let rects = squarify(weights, area);
+        assert_eq!(rects.len(), weights.len());
+        for (i, r) in rects.iter().enumerate() {
+            if r.is_empty() {
+                continue;
+            }
+            assert_eq!(area.intersection(*r), *r, "block {} {:?} leaves {:?}", i, r, area);
+            for (j, other) in rects.iter().enumerate().skip(i + 1) {
+                assert!(!r.intersects(*other), "blocks {} {:?} and {} {:?} overlap", i, r, j, other);
+            }
+        }
+        rects
+    }
+
+    #[test]
+    fn squarify_fills_the_area_without_overlap() {
+        let area = Rect::new(3, 2, 80, 20);
+        let weights = [50.0, 20.0, 10.0, 8.0, 5.0, 4.0, 2.0, 1.0];
+        let rects = assert_tiles(&weights, area);
+        assert!(rects.iter().all(|r| !r.is_empty()));
+        let covered: u32 = rects.iter().map(|r| r.area()).sum();
+        assert_eq!(covered, area.area());
+    }
+
+    #[test]
+    fn squarify_skips_zero_and_tiny_weights() {
+        let area = Rect::new(0, 0, 60, 15);
+        let weights = [0.0, 1e12, 3e11, 0.0, 1e-6, 2.0, -5.0, 1e9];
+        let rects = assert_tiles(&weights, area);
+        assert!(rects[0].is_empty() && rects[3].is_empty() && rects[6].is_empty());
+        assert!(!rects[1].is_empty());
+        assert!(squarify(&[0.0, 0.0], area).iter().all(|r| r.is_empty()));
+    }
+
+    #[test]
+    fn squarify_handles_thin_areas() {
+        let weights: Vec<f64> = (1..=30).map(|i| 100.0 / i as f64).collect();
+        for area in [
+            Rect::new(0, 0, 100, 1),
+            Rect::new(5, 5, 1, 40),
+            Rect::new(0, 0, 3, 2),
+            Rect::new(0, 0, 0, 10),
+        ] {
+            assert_tiles(&weights, area);
+        }
+    }
+
+    #[test]
+    fn squarify_never_overlaps_across_sizes() {
+        let sets: [&[f64]; 3] = [
+            &[1.0; 12],
+            &[900.0, 300.0, 120.0, 80.0, 33.3, 12.5, 7.0, 1.0, 0.5, 0.01],
+            &[3.0, 1e-9, 3.0, 0.0, 2.9999, 1.0000001],
+        ];
+        for weights in sets {
+            for width in 1..=40 {
+                for height in 1..=12 {
+                    assert_tiles(weights, Rect::new(2, 1, width, height));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn price_chart_survives_tiny_areas() {
+        let mut app = App::new(crate::config::Config::default(), false);
+        app.chart_cursor = Some(3);
+        let history = PriceHistory {
+            timestamps: (0..8).map(|i| i * 3600).collect(),
+            prices: vec![10.0, 11.0, 9.5, 12.0, 12.5, 11.0, 13.0, 12.0],
+            volumes: vec![5.0, 7.0, 6.0, 9.0, 4.0, 8.0, 6.0, 7.0],
+        };
+        let backend = ratatui::backend::TestBackend::new(24, 16);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        for width in 0..=24 {
+            for height in 0..=16 {
+                let area = Rect::new(0, 0, width, height);
+                terminal
+                    .draw(|f| {
+                        draw_price_chart(f, &app, &history, None, area, Color::Green);
+                    })
+                    .unwrap();
+            }
+        }
+    }
+}